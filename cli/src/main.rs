@@ -0,0 +1,165 @@
+//! Offline tooling for the Simo Payment Distributor: split quotes and instruction decoding
+
+use std::process::ExitCode;
+
+use solana_program::pubkey::Pubkey;
+
+use simo_distribution_client::{
+    compute_distribution_split, parse_instruction, DistributorConfig, ParsedInstruction, Splits,
+};
+
+const USAGE: &str = "\
+usage:
+  simo-distribution split <lamports> [--first] [--second] [--config <hex>] [--treasury-balance <lamports>]
+      Print the split the program pays. --config takes GetConfig return data as hex (from a
+      simulation against the cluster); without it the parameters this tool was built with are used
+  simo-distribution decode <hex>
+      Decode distribution or GetConfig instruction data";
+
+// Rent-exempt minimum for a zero-data account at current rent, for the built-in config
+const RENT_EXEMPT_MINIMUM: u64 = 890_880;
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match run(&args) {
+        Ok(output) => {
+            println!("{output}");
+            ExitCode::SUCCESS
+        }
+        Err(error) => {
+            eprintln!("error: {error}\n\n{USAGE}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(args: &[String]) -> Result<String, String> {
+    match args {
+        [command, rest @ ..] if command == "split" => split(rest),
+        [command, hex] if command == "decode" => decode(hex),
+        _ => Err("expected a command".into()),
+    }
+}
+
+fn split(args: &[String]) -> Result<String, String> {
+    let mut lamports = None;
+    let mut has_first_referrer = false;
+    let mut has_second_referrer = false;
+    let mut config = None;
+    let mut treasury_balance = None;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--first" => has_first_referrer = true,
+            "--second" => has_second_referrer = true,
+            "--config" => {
+                let data = hex_bytes(args.next().ok_or("--config needs a value")?)?;
+                config = Some(DistributorConfig::from_return_data(&data).ok_or("--config must be 40 bytes")?);
+            }
+            "--treasury-balance" => treasury_balance = Some(lamports_arg(args.next())?),
+            _ if lamports.is_none() => lamports = Some(lamports_arg(Some(arg))?),
+            _ => return Err(format!("unexpected argument {arg}")),
+        }
+    }
+
+    let config = config.unwrap_or_else(|| {
+        let data = simo_distribution_client::config_return_data(RENT_EXEMPT_MINIMUM);
+        DistributorConfig::from_return_data(&data).unwrap()
+    });
+    let lamports = lamports.ok_or("split needs an amount in lamports")?;
+    let splits = compute_distribution_split(
+        lamports,
+        has_first_referrer,
+        has_second_referrer,
+        &config,
+        treasury_balance,
+        None,
+    );
+    Ok(format_splits(&splits))
+}
+
+fn decode(hex: &str) -> Result<String, String> {
+    // Keys aren't part of the data; placeholders for the full layout let the parser run
+    let keys = [Pubkey::default(); 9];
+    let parsed = parse_instruction(&hex_bytes(hex)?, &keys).map_err(|error| format!("{error:?}"))?;
+    let ParsedInstruction::Distribution(parsed) = parsed else {
+        return Ok("GetConfig".into());
+    };
+
+    let amount = if parsed.sweep_all { "sweep all".to_string() } else { parsed.amount.to_string() };
+    let mut output = format!(
+        "Distribution\namount           {amount}\nfirst referrer   {}\nsecond referrer  {}\ntip              {}",
+        parsed.first_referrer.is_some(),
+        parsed.second_referrer.is_some(),
+        parsed.tip,
+    );
+    if let Some(quote) = parsed.expected_split {
+        output.push_str("\nquote\n");
+        output.push_str(&format_splits(&quote));
+    }
+    Ok(output)
+}
+
+fn format_splits(splits: &Splits) -> String {
+    [
+        ("treasury", splits.treasury),
+        ("team", splits.team),
+        ("first referrer", splits.first_referrer),
+        ("second referrer", splits.second_referrer),
+        ("protocol fee", splits.protocol_fee),
+    ]
+    .map(|(payee, lamports)| format!("{payee:<17}{lamports}"))
+    .join("\n")
+}
+
+fn lamports_arg(arg: Option<&String>) -> Result<u64, String> {
+    let arg = arg.ok_or("expected an amount in lamports")?;
+    arg.parse().map_err(|_| format!("invalid amount {arg}"))
+}
+
+fn hex_bytes(hex: &str) -> Result<Vec<u8>, String> {
+    let hex = hex.trim().trim_start_matches("0x");
+    if !hex.is_ascii() || !hex.len().is_multiple_of(2) {
+        return Err(format!("invalid hex {hex}"));
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).map_err(|_| format!("invalid hex {hex}")))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use simo_distribution_client::{add_tip, compute_splits, distribution};
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn split_prints_the_program_split() {
+        assert_eq!(
+            run(&args("split 1000000000 --first --second")),
+            Ok(format_splits(&compute_splits(1_000_000_000, true, true)))
+        );
+        assert!(run(&args("split --first")).is_err());
+    }
+
+    #[test]
+    fn decode_reads_back_a_built_instruction() {
+        let key = Pubkey::new_unique();
+        let quote = compute_splits(1_000, true, false);
+        let mut instruction = distribution(&key, &key, &key, [Some(&key), None], &key, 1_000, Some(&quote));
+        add_tip(&mut instruction, &key, 5);
+        let hex: String = instruction.data.iter().map(|byte| format!("{byte:02x}")).collect();
+
+        let output = run(&["decode".into(), hex]).unwrap();
+
+        assert!(output.starts_with("Distribution\namount           1000\nfirst referrer   true\n"));
+        assert!(output.contains("tip              5\nquote\n"));
+        assert!(output.ends_with(&format_splits(&quote)));
+        assert_eq!(run(&args("decode 00")), Ok("GetConfig".into()));
+    }
+}
@@ -6,11 +6,24 @@
  */
 
 import { 
+  Connection,
   PublicKey, 
   TransactionInstruction,
+  TransactionMessage,
+  VersionedTransaction,
   SystemProgram,
-  LAMPORTS_PER_SOL
+  SYSVAR_INSTRUCTIONS_PUBKEY,
+  LAMPORTS_PER_SOL,
+  type RpcResponseAndContext,
+  type SimulatedTransactionResponse
 } from '@solana/web3.js';
+import bs58 from 'bs58';
+
+/** Amount sentinel: distribute the payer's balance minus the rent-exempt minimum */
+export const SWEEP_ALL = 0xffffffffffffffffn;
+
+/** SPL Memo program, used to attach an order reference to a payment */
+const MEMO_PROGRAM_ID = new PublicKey('MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr');
 
 /**
  * Parameters for creating a payment distribution instruction
@@ -20,16 +33,37 @@ export interface PaymentDistributionParams {
   programId: string;
   /** The wallet address of the payer */
   payer: string;
-  /** The amount to pay in SOL */
-  amount: number;
+  /** The amount to pay in SOL (ignored when lamports is set) */
+  amount?: number;
+  /** Exact amount in lamports, or SWEEP_ALL to distribute the payer's balance (optional) */
+  lamports?: bigint | null;
   /** The treasury wallet address */
   treasuryWallet: string;
   /** The team wallet address */
   teamWallet: string;
+  /** The protocol fee wallet address */
+  protocolFeeWallet: string;
   /** The first referrer wallet address (optional) */
   firstReferrer?: string | null;
   /** The second referrer wallet address (optional) */
   secondReferrer?: string | null;
+  /** Quoted split in lamports the program must reproduce exactly (optional) */
+  expectedSplit?: ExpectedSplit | null;
+  /** Tip in lamports paid on top of the amount, entirely to tipsWallet (optional) */
+  tip?: bigint;
+  /** The tips wallet address (required with a tip) */
+  tipsWallet?: string | null;
+}
+
+/**
+ * Lamport amounts per recipient, as shown to the user before signing
+ */
+export interface ExpectedSplit {
+  treasury: bigint;
+  team: bigint;
+  firstReferrer: bigint;
+  secondReferrer: bigint;
+  protocolFee: bigint;
 }
 
 /**
@@ -42,6 +76,10 @@ export interface CreateInstructionsParams {
   referralCode?: string;
   /** The wallet address of the payer */
   payer: string;
+  /** Tip in SOL paid on top of the amount to the tips wallet (optional) */
+  tip?: number;
+  /** Order reference attached as an SPL Memo (required when the program enforces memos) */
+  memo?: string;
 }
 
 /**
@@ -52,25 +90,49 @@ export interface CreateInstructionsParams {
 export function createPaymentDistributionInstruction({
   programId,
   payer,
-  amount,
+  amount = 0,
+  lamports = null,
   treasuryWallet,
   teamWallet,
+  protocolFeeWallet,
   firstReferrer = null,
-  secondReferrer = null
+  secondReferrer = null,
+  expectedSplit = null,
+  tip = 0n,
+  tipsWallet = null
 }: PaymentDistributionParams): TransactionInstruction {
-  // Convert amount to lamports (1 SOL = 1,000,000,000 lamports)
-  const lamports = Math.floor(amount * LAMPORTS_PER_SOL);
+  if (tip > 0n && !tipsWallet) {
+    throw new Error('A tip needs a tips wallet');
+  }
+
+  // Convert amount to lamports (1 SOL = 1,000,000,000 lamports) unless given exactly
+  const amountLamports = lamports ?? BigInt(Math.floor(amount * LAMPORTS_PER_SOL));
   
   // Create instruction data buffer
   // Format: [amount (8 bytes), hasFirstReferrer (1 byte), hasSecondReferrer (1 byte)]
-  const data = Buffer.alloc(10);
+  // optionally followed by the expected split (5 x u64), rejected on-chain on mismatch,
+  // and then by the tip (u64)
+  const splitLength = expectedSplit ? 50 : 10;
+  const data = Buffer.alloc(tip > 0n ? splitLength + 8 : splitLength);
   
   // Write amount as little-endian u64 (8 bytes)
-  data.writeBigUInt64LE(BigInt(lamports), 0);
+  data.writeBigUInt64LE(amountLamports, 0);
   
   // Write referrer flags
   data.writeUInt8(firstReferrer ? 1 : 0, 8);
   data.writeUInt8(secondReferrer ? 1 : 0, 9);
+
+  if (expectedSplit) {
+    data.writeBigUInt64LE(expectedSplit.treasury, 10);
+    data.writeBigUInt64LE(expectedSplit.team, 18);
+    data.writeBigUInt64LE(expectedSplit.firstReferrer, 26);
+    data.writeBigUInt64LE(expectedSplit.secondReferrer, 34);
+    data.writeBigUInt64LE(expectedSplit.protocolFee, 42);
+  }
+
+  if (tip > 0n) {
+    data.writeBigUInt64LE(tip, splitLength);
+  }
   
   // Create account keys array
   const keys = [
@@ -98,8 +160,19 @@ export function createPaymentDistributionInstruction({
     },
     
     // System program
-    { pubkey: SystemProgram.programId, isSigner: false, isWritable: false }
+    { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
+
+    // Protocol fee wallet (writable)
+    { pubkey: new PublicKey(protocolFeeWallet), isSigner: false, isWritable: true },
+
+    // Instructions sysvar (read when the program requires a memo, ignored otherwise)
+    { pubkey: SYSVAR_INSTRUCTIONS_PUBKEY, isSigner: false, isWritable: false }
   ];
+
+  // Tips wallet (writable), only with a tip
+  if (tip > 0n && tipsWallet) {
+    keys.push({ pubkey: new PublicKey(tipsWallet), isSigner: false, isWritable: true });
+  }
   
   // Create and return the instruction
   return new TransactionInstruction({
@@ -109,6 +182,325 @@ export function createPaymentDistributionInstruction({
   });
 }
 
+/**
+ * Split parameters reported by the GetConfig query
+ */
+export interface DistributorConfig {
+  treasuryPct: number;
+  referralPct: number;
+  referralMax: bigint;
+  referralDecayShift: number;
+  referralTotalPctMax: number;
+  protocolFeeBps: number;
+  /** Left behind in the payer by SWEEP_ALL, and what fresh referrers are topped up to */
+  rentExemptMinimum: bigint;
+  treasurySoftCap: bigint;
+  referralMinBalance: bigint;
+  requireMemo: boolean;
+  topUpFreshReferrers: boolean;
+}
+
+/**
+ * Create a read-only GetConfig instruction, answered via return data
+ * (use with simulateTransaction and decode with decodeDistributorConfig)
+ * @param programId The program ID of the payment distributor contract
+ * @returns The transaction instruction
+ */
+export function createGetConfigInstruction(programId: string): TransactionInstruction {
+  return new TransactionInstruction({
+    keys: [],
+    programId: new PublicKey(programId),
+    data: Buffer.from([0])
+  });
+}
+
+/**
+ * Decode the return data of a GetConfig instruction
+ * @param data Raw return data (40 bytes)
+ * @returns The decoded split parameters
+ */
+export function decodeDistributorConfig(data: Buffer): DistributorConfig {
+  return {
+    treasuryPct: data.readUInt8(0),
+    referralPct: data.readUInt8(1),
+    referralMax: data.readBigUInt64LE(2),
+    referralDecayShift: data.readUInt8(10),
+    referralTotalPctMax: data.readUInt8(11),
+    protocolFeeBps: data.readUInt16LE(12),
+    rentExemptMinimum: data.readBigUInt64LE(14),
+    treasurySoftCap: data.readBigUInt64LE(22),
+    referralMinBalance: data.readBigUInt64LE(30),
+    requireMemo: data.readUInt8(38) !== 0,
+    topUpFreshReferrers: data.readUInt8(39) !== 0
+  };
+}
+
+/**
+ * Simulate instructions without signing them, against the latest blockhash
+ * @param connection RPC connection
+ * @param instructions Instructions to simulate, in order
+ * @param feePayer Any existing account to pay for the simulation (nothing is signed or sent)
+ * @returns The simulation result, including logs and return data
+ */
+export async function simulateInstructions(
+  connection: Connection,
+  instructions: TransactionInstruction[],
+  feePayer: string
+): Promise<RpcResponseAndContext<SimulatedTransactionResponse>> {
+  const message = new TransactionMessage({
+    payerKey: new PublicKey(feePayer),
+    recentBlockhash: PublicKey.default.toBase58(), // Replaced by the RPC node
+    instructions
+  }).compileToV0Message();
+  return connection.simulateTransaction(new VersionedTransaction(message), {
+    sigVerify: false,
+    replaceRecentBlockhash: true
+  });
+}
+
+/**
+ * Fetch the live split parameters by simulating a GetConfig instruction
+ * @param connection RPC connection
+ * @param programId The program ID of the payment distributor contract
+ * @param feePayer Any existing account to pay for the simulation
+ * @returns The decoded split parameters
+ */
+export async function fetchDistributorConfig(
+  connection: Connection,
+  programId: string,
+  feePayer: string
+): Promise<DistributorConfig> {
+  const { value } = await simulateInstructions(connection, [createGetConfigInstruction(programId)], feePayer);
+  if (value.err) {
+    throw new Error(`GetConfig failed: ${JSON.stringify(value.err)}`);
+  }
+  if (!value.returnData || value.returnData.programId !== programId) {
+    throw new Error('GetConfig returned no data');
+  }
+  return decodeDistributorConfig(Buffer.from(value.returnData.data[0], 'base64'));
+}
+
+/**
+ * Recipient wallets of a distribution, for mirroring adjustments that depend on them
+ */
+export interface SplitRecipients {
+  treasury: string;
+  team: string;
+  /** Protocol fee wallet, or null for six-account instructions (the program pays the fee to the team) */
+  protocolFee: string | null;
+  firstReferrer: string;
+  secondReferrer: string;
+  /** Referrer balances before the payment, for the fresh-wallet top-up */
+  firstReferrerBalance: bigint;
+  secondReferrerBalance: bigint;
+}
+
+/**
+ * Compute the split exactly as the program does, including its integer rounding.
+ * Flags should already reflect referrer eligibility (no self-referral, minimum balance).
+ * @param lamports The amount to distribute in lamports
+ * @param hasFirstReferrer Whether a first referrer is paid
+ * @param hasSecondReferrer Whether a second referrer is paid
+ * @param config Live parameters from the GetConfig query
+ * @param treasuryBalance Current treasury balance, to apply the soft cap (optional)
+ * @param recipients Recipient wallets, to apply the six-account fee fallback and fresh-wallet top-ups (optional)
+ * @returns Lamports per recipient, usable as expectedSplit
+ */
+export function computeDistributionSplit(
+  lamports: bigint,
+  hasFirstReferrer: boolean,
+  hasSecondReferrer: boolean,
+  config: DistributorConfig,
+  treasuryBalance?: bigint,
+  recipients?: SplitRecipients
+): ExpectedSplit {
+  const referralShare = (level: number): bigint => {
+    const shift = BigInt(config.referralDecayShift * level);
+    const share = (lamports * BigInt(config.referralPct) / 100n) >> shift;
+    const cap = config.referralMax >> shift;
+    return share < cap ? share : cap;
+  };
+  const min = (a: bigint, b: bigint): bigint => (a < b ? a : b);
+
+  let treasury = lamports * BigInt(config.treasuryPct) / 100n;
+
+  let referralBudget = lamports * BigInt(config.referralTotalPctMax) / 100n;
+  let firstReferrer = hasFirstReferrer ? min(referralShare(0), referralBudget) : 0n;
+  referralBudget -= firstReferrer;
+  let secondReferrer = hasSecondReferrer ? min(referralShare(1), referralBudget) : 0n;
+
+  let protocolFee = lamports * BigInt(config.protocolFeeBps) / 10000n;
+
+  let team = lamports - treasury - firstReferrer - secondReferrer - protocolFee;
+
+  // Six-account instructions predate the protocol fee; the program pays it to the team
+  if (recipients && recipients.protocolFee === null) {
+    team += protocolFee;
+    protocolFee = 0n;
+  }
+
+  // Treasury overflow is routed to the team wallet
+  if (treasuryBalance !== undefined && treasuryBalance >= config.treasurySoftCap) {
+    team += treasury;
+    treasury = 0n;
+  }
+
+  // Empty referrer wallets are topped up to rent-exempt from the treasury share, or treated as
+  // absent (share to team) when the treasury share can't cover the whole top-up. The program
+  // pays every slot a wallet fills in one transfer, so the wallet's combined payout counts
+  if (config.topUpFreshReferrers && recipients) {
+    const payoutTo = (wallet: string): bigint =>
+      (recipients.treasury === wallet ? treasury : 0n) +
+      (recipients.team === wallet ? team : 0n) +
+      ((recipients.protocolFee ?? recipients.team) === wallet ? protocolFee : 0n) +
+      (recipients.firstReferrer === wallet ? firstReferrer : 0n) +
+      (recipients.secondReferrer === wallet ? secondReferrer : 0n);
+    const topUp = (share: bigint, wallet: string, balance: bigint): bigint => {
+      const payout = payoutTo(wallet);
+      if (share === 0n || payout >= config.rentExemptMinimum || balance !== 0n) {
+        return share;
+      }
+      const amount = config.rentExemptMinimum - payout;
+      // Moving treasury lamports to the treasury wallet itself wouldn't raise its payout
+      const available = recipients.treasury === wallet ? 0n : treasury;
+      if (amount > available) {
+        team += share;
+        return 0n;
+      }
+      treasury -= amount;
+      return share + amount;
+    };
+    firstReferrer = topUp(firstReferrer, recipients.firstReferrer, recipients.firstReferrerBalance);
+    secondReferrer = topUp(secondReferrer, recipients.secondReferrer, recipients.secondReferrerBalance);
+  }
+
+  return { treasury, team, firstReferrer, secondReferrer, protocolFee };
+}
+
+/**
+ * Outcome of reconciling one distribution instruction against its inner transfers
+ */
+export interface SettlementVerification {
+  /** Index of the distribution instruction in the transaction */
+  instructionIndex: number;
+  /** Lamports each wallet should have received */
+  expected: Record<string, bigint>;
+  /** Lamports each wallet actually received from the instruction's transfers */
+  actual: Record<string, bigint>;
+  /** Whether expected and actual agree exactly */
+  matches: boolean;
+}
+
+/**
+ * Verify that every distribution in a confirmed transaction paid out the split the
+ * program should have computed. Eligibility and the treasury soft cap are evaluated
+ * against the transaction's pre-balances, so this assumes no earlier instruction in
+ * the same transaction moved those balances. Sweep payments are checked against the
+ * total actually distributed. Tips are expected in full at the tips wallet.
+ * @param connection RPC connection
+ * @param signature Signature of the confirmed transaction
+ * @param programId The program ID of the payment distributor contract
+ * @param config Parameters from the GetConfig query at the program version that ran
+ * @returns One verification per distribution instruction in the transaction
+ */
+export async function verifySettlement(
+  connection: Connection,
+  signature: string,
+  programId: string,
+  config: DistributorConfig
+): Promise<SettlementVerification[]> {
+  const tx = await connection.getParsedTransaction(signature, {
+    commitment: 'confirmed',
+    maxSupportedTransactionVersion: 0
+  });
+  if (!tx || !tx.meta) {
+    throw new Error(`Transaction ${signature} not found`);
+  }
+  if (tx.meta.err) {
+    throw new Error(`Transaction ${signature} failed: ${JSON.stringify(tx.meta.err)}`);
+  }
+
+  const meta = tx.meta;
+  const accountKeys = tx.transaction.message.accountKeys.map(key => key.pubkey.toBase58());
+  const preBalance = (key: string): bigint => BigInt(meta.preBalances[accountKeys.indexOf(key)]);
+  const add = (totals: Record<string, bigint>, key: string, lamports: bigint) => {
+    if (lamports > 0n) {
+      totals[key] = (totals[key] ?? 0n) + lamports;
+    }
+  };
+
+  const results: SettlementVerification[] = [];
+
+  tx.transaction.message.instructions.forEach((ix, instructionIndex) => {
+    if (ix.programId.toBase58() !== programId || !('data' in ix)) {
+      return;
+    }
+
+    // Short instruction data is a read-only query, not a distribution
+    const data = Buffer.from(bs58.decode(ix.data));
+    if (data.length < 8) {
+      return;
+    }
+
+    const [payer, treasury, team, firstReferrer, secondReferrer, , protocolFeeWallet, , tipsWallet] =
+      ix.accounts.map(key => key.toBase58());
+
+    // Sum what the system program actually moved for this instruction
+    const actual: Record<string, bigint> = {};
+    const inner = meta.innerInstructions?.find(set => set.index === instructionIndex);
+    for (const innerIx of inner?.instructions ?? []) {
+      if ('parsed' in innerIx && innerIx.program === 'system' && innerIx.parsed.type === 'transfer') {
+        add(actual, innerIx.parsed.info.destination, BigInt(innerIx.parsed.info.lamports));
+      }
+    }
+
+    // The tip is the trailing u64 of an 18 or 58 byte instruction
+    const tip = data.length === 18 || data.length === 58 ? data.readBigUInt64LE(data.length - 8) : 0n;
+
+    const requested = data.readBigUInt64LE(0);
+    const lamports = requested === SWEEP_ALL
+      ? Object.values(actual).reduce((sum, value) => sum + value, 0n) - tip
+      : requested;
+
+    // Mirror the program's eligibility checks
+    const eligible = (flagOffset: number, key: string): boolean =>
+      data.length > flagOffset && data[flagOffset] !== 0 &&
+      key !== payer && preBalance(key) >= config.referralMinBalance;
+
+    const split = computeDistributionSplit(
+      lamports,
+      eligible(8, firstReferrer),
+      eligible(9, secondReferrer),
+      config,
+      preBalance(treasury),
+      {
+        treasury,
+        team,
+        protocolFee: ix.accounts.length > 6 ? protocolFeeWallet : null,
+        firstReferrer,
+        secondReferrer,
+        firstReferrerBalance: preBalance(firstReferrer),
+        secondReferrerBalance: preBalance(secondReferrer)
+      }
+    );
+
+    const expected: Record<string, bigint> = {};
+    add(expected, treasury, split.treasury);
+    add(expected, team, split.team);
+    add(expected, protocolFeeWallet, split.protocolFee);
+    add(expected, firstReferrer, split.firstReferrer);
+    add(expected, secondReferrer, split.secondReferrer);
+    add(expected, tipsWallet, tip);
+
+    const keys = new Set([...Object.keys(expected), ...Object.keys(actual)]);
+    const matches = [...keys].every(key => expected[key] === actual[key]);
+
+    results.push({ instructionIndex, expected, actual, matches });
+  });
+
+  return results;
+}
+
 /**
  * Response from the referrer API
  */
@@ -129,7 +521,9 @@ interface ReferrerResponse {
 export async function createPaymentDistributionInstructions({ 
   amount, 
   referralCode, 
-  payer 
+  payer,
+  tip = 0,
+  memo
 }: CreateInstructionsParams): Promise<TransactionInstruction[]> {
   // Import configuration
   const { SolanaConfig } = await import('../config/solana.config.js');
@@ -173,10 +567,24 @@ export async function createPaymentDistributionInstructions({
     amount,
     treasuryWallet: SolanaConfig.TREASURY_WALLET,
     teamWallet: SolanaConfig.TEAM_WALLET,
+    protocolFeeWallet: SolanaConfig.PROTOCOL_FEE_WALLET,
     firstReferrer,
-    secondReferrer
+    secondReferrer,
+    tip: BigInt(Math.floor(tip * LAMPORTS_PER_SOL)),
+    tipsWallet: SolanaConfig.TIPS_WALLET
   });
   
+  const instructions = [instruction];
+
+  // The memo can sit anywhere in the transaction; the program scans for it
+  if (memo) {
+    instructions.unshift(new TransactionInstruction({
+      keys: [{ pubkey: new PublicKey(payer), isSigner: true, isWritable: false }],
+      programId: MEMO_PROGRAM_ID,
+      data: Buffer.from(memo, 'utf8')
+    }));
+  }
+  
   // Return as an array to match the expected interface
-  return [instruction];
+  return instructions;
 }
\ No newline at end of file
@@ -0,0 +1,185 @@
+/**
+ * Split simulator for support questions about amounts
+ *
+ * Prints the exact split the deployed program pays for an amount, computed from its live
+ * config, and optionally simulates the distribution against the cluster with that split as
+ * the quote, so the program itself confirms it:
+ *
+ *   npx tsx simulate-split.ts --url <rpc> --program <id> --payer <wallet> --lamports <n>
+ *     [--first <wallet>] [--second <wallet>]
+ *     [--treasury <wallet> --team <wallet> --protocol-fee <wallet> [--simulate]]
+ */
+
+import { pathToFileURL } from 'node:url';
+import { parseArgs } from 'node:util';
+import { Connection, PublicKey } from '@solana/web3.js';
+import {
+  computeDistributionSplit,
+  createPaymentDistributionInstruction,
+  fetchDistributorConfig,
+  simulateInstructions,
+  type DistributorConfig,
+  type ExpectedSplit
+} from './direct-web3-client.js';
+
+/**
+ * Parameters for quoting a split
+ */
+export interface SplitQuoteParams {
+  /** The program ID of the payment distributor contract */
+  programId: string;
+  /** The wallet address of the payer (also pays for the simulations) */
+  payer: string;
+  /** The amount to distribute in lamports */
+  lamports: bigint;
+  /** The first referrer wallet address (optional) */
+  firstReferrer?: string | null;
+  /** The second referrer wallet address (optional) */
+  secondReferrer?: string | null;
+  /** Recipient wallets, to apply the soft cap and fresh-wallet top-ups and to simulate (optional) */
+  wallets?: { treasury: string; team: string; protocolFee: string } | null;
+}
+
+/**
+ * A split computed from the live config
+ */
+export interface SplitQuote {
+  config: DistributorConfig;
+  split: ExpectedSplit;
+}
+
+/**
+ * Compute the split the program would pay right now
+ * @param connection RPC connection
+ * @param params Amount, referrers and recipient wallets
+ * @returns The live config and the split
+ */
+export async function quoteSplit(connection: Connection, params: SplitQuoteParams): Promise<SplitQuote> {
+  const { programId, payer, lamports, firstReferrer = null, secondReferrer = null, wallets = null } = params;
+  const config = await fetchDistributorConfig(connection, programId, payer);
+  const balance = async (wallet: string): Promise<bigint> =>
+    BigInt(await connection.getBalance(new PublicKey(wallet)));
+
+  // Mirror the program's eligibility checks; absent referrers are the payer, as on-chain
+  const first = firstReferrer ?? payer;
+  const second = secondReferrer ?? payer;
+  const [firstBalance, secondBalance] = await Promise.all([balance(first), balance(second)]);
+  const eligible = (wallet: string | null, walletBalance: bigint): boolean =>
+    wallet !== null && wallet !== payer && walletBalance >= config.referralMinBalance;
+
+  const split = computeDistributionSplit(
+    lamports,
+    eligible(firstReferrer, firstBalance),
+    eligible(secondReferrer, secondBalance),
+    config,
+    wallets ? await balance(wallets.treasury) : undefined,
+    wallets
+      ? {
+          treasury: wallets.treasury,
+          team: wallets.team,
+          protocolFee: wallets.protocolFee,
+          firstReferrer: first,
+          secondReferrer: second,
+          firstReferrerBalance: firstBalance,
+          secondReferrerBalance: secondBalance
+        }
+      : undefined
+  );
+
+  return { config, split };
+}
+
+/**
+ * Simulate the distribution with the quoted split; the program rejects it if its split differs
+ * @param connection RPC connection
+ * @param params The parameters the split was quoted with, including the recipient wallets
+ * @param split The quoted split
+ * @returns The simulation error (null on success) and program logs
+ */
+export async function simulateSplit(
+  connection: Connection,
+  params: SplitQuoteParams,
+  split: ExpectedSplit
+): Promise<{ err: unknown; logs: string[] }> {
+  if (!params.wallets) {
+    throw new Error('Simulating needs the treasury, team and protocol fee wallets');
+  }
+  const instruction = createPaymentDistributionInstruction({
+    programId: params.programId,
+    payer: params.payer,
+    lamports: params.lamports,
+    treasuryWallet: params.wallets.treasury,
+    teamWallet: params.wallets.team,
+    protocolFeeWallet: params.wallets.protocolFee,
+    firstReferrer: params.firstReferrer,
+    secondReferrer: params.secondReferrer,
+    expectedSplit: split
+  });
+  const { value } = await simulateInstructions(connection, [instruction], params.payer);
+  return { err: value.err, logs: value.logs ?? [] };
+}
+
+/**
+ * Lamports per recipient, one per line
+ * @param split The split to print
+ * @returns The formatted split
+ */
+export function formatSplit(split: ExpectedSplit): string {
+  return [
+    ['treasury', split.treasury],
+    ['team', split.team],
+    ['first referrer', split.firstReferrer],
+    ['second referrer', split.secondReferrer],
+    ['protocol fee', split.protocolFee]
+  ].map(([payee, lamports]) => `${String(payee).padEnd(17)}${lamports}`).join('\n');
+}
+
+async function main(): Promise<void> {
+  const { values } = parseArgs({
+    options: {
+      url: { type: 'string' },
+      program: { type: 'string' },
+      payer: { type: 'string' },
+      lamports: { type: 'string' },
+      first: { type: 'string' },
+      second: { type: 'string' },
+      treasury: { type: 'string' },
+      team: { type: 'string' },
+      'protocol-fee': { type: 'string' },
+      simulate: { type: 'boolean', default: false }
+    }
+  });
+  if (!values.url || !values.program || !values.payer || !values.lamports) {
+    throw new Error('--url, --program, --payer and --lamports are required');
+  }
+
+  const connection = new Connection(values.url, 'confirmed');
+  const protocolFee = values['protocol-fee'];
+  const params: SplitQuoteParams = {
+    programId: values.program,
+    payer: values.payer,
+    lamports: BigInt(values.lamports),
+    firstReferrer: values.first ?? null,
+    secondReferrer: values.second ?? null,
+    wallets: values.treasury && values.team && protocolFee
+      ? { treasury: values.treasury, team: values.team, protocolFee }
+      : null
+  };
+
+  const { split } = await quoteSplit(connection, params);
+  console.log(formatSplit(split));
+
+  if (values.simulate) {
+    const { err, logs } = await simulateSplit(connection, params, split);
+    console.log(`\nsimulation: ${err ? `failed ${JSON.stringify(err)}` : 'ok'}`);
+    logs.forEach(line => console.log(`  ${line}`));
+    process.exitCode = err ? 1 : 0;
+  }
+}
+
+if (process.argv[1] && import.meta.url === pathToFileURL(process.argv[1]).href) {
+  main().catch(err => {
+    console.error(err instanceof Error ? err.message : err);
+    process.exitCode = 1;
+  });
+}
@@ -0,0 +1,193 @@
+//! Off-chain helpers for the Simo Payment Distributor, mirroring client/direct-web3-client.ts
+
+use solana_program::pubkey::Pubkey;
+
+use simo_distribution_interface::{share_of, top_up_fresh_referrers, Payees, CONFIG_LEN};
+
+pub use simo_distribution_interface::{
+    instruction::{add_tip, distribution, get_config},
+    compute_splits, config_return_data, parse_instruction, ParsedDistribution, ParsedInstruction, Splits, ID,
+};
+
+// Split parameters reported by the GetConfig query
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DistributorConfig {
+    pub treasury_pct: u8,
+    pub ref_pct: u8,
+    pub ref_max: u64,
+    pub ref_decay_shift: u32,
+    pub ref_total_pct_max: u8,
+    pub protocol_fee_bps: u16,
+    pub rent_exempt_minimum: u64, // Left behind in the payer by SWEEP_ALL, and what fresh referrers are topped up to
+    pub treasury_soft_cap: u64,
+    pub ref_min_balance: u64,
+    pub require_memo: bool,
+    pub top_up_fresh_referrers: bool,
+}
+
+impl DistributorConfig {
+    // Decode GetConfig return data (layout in the interface crate); None if it isn't 40 bytes
+    pub fn from_return_data(data: &[u8]) -> Option<Self> {
+        if data.len() != CONFIG_LEN {
+            return None;
+        }
+        let word = |at: usize| u64::from_le_bytes(data[at..at + 8].try_into().unwrap());
+        Some(Self {
+            treasury_pct: data[0],
+            ref_pct: data[1],
+            ref_max: word(2),
+            ref_decay_shift: u32::from(data[10]),
+            ref_total_pct_max: data[11],
+            protocol_fee_bps: u16::from_le_bytes([data[12], data[13]]),
+            rent_exempt_minimum: word(14),
+            treasury_soft_cap: word(22),
+            ref_min_balance: word(30),
+            require_memo: data[38] != 0,
+            top_up_fresh_referrers: data[39] != 0,
+        })
+    }
+}
+
+// Recipient wallets of a distribution, for mirroring adjustments that depend on them
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SplitRecipients {
+    pub treasury: Pubkey,
+    pub team: Pubkey,
+    pub protocol_fee: Option<Pubkey>, // None for six-account instructions, whose fee goes to the team
+    pub first_referrer: Pubkey,
+    pub second_referrer: Pubkey,
+    pub first_referrer_balance: u64, // Balances before the payment, for the fresh-wallet top-up
+    pub second_referrer_balance: u64,
+}
+
+// The split the program computes from a live config, including its rounding. Flags should already
+// reflect referrer eligibility; the treasury balance applies the soft cap and the recipients the
+// six-account fee fallback and fresh-wallet top-ups
+pub fn compute_distribution_split(
+    lamports: u64,
+    has_first_referrer: bool,
+    has_second_referrer: bool,
+    config: &DistributorConfig,
+    treasury_balance: Option<u64>,
+    recipients: Option<&SplitRecipients>,
+) -> Splits {
+    let referral_share = |level: u32| {
+        let shift = config.ref_decay_shift * level;
+        let share = share_of(lamports, u64::from(config.ref_pct), 100).checked_shr(shift).unwrap_or(0);
+        share.min(config.ref_max.checked_shr(shift).unwrap_or(0))
+    };
+
+    let treasury = share_of(lamports, u64::from(config.treasury_pct), 100);
+
+    let mut referral_budget = share_of(lamports, u64::from(config.ref_total_pct_max), 100);
+    let first_referrer = if has_first_referrer { referral_share(0).min(referral_budget) } else { 0 };
+    referral_budget -= first_referrer;
+    let second_referrer = if has_second_referrer { referral_share(1).min(referral_budget) } else { 0 };
+
+    let protocol_fee = share_of(lamports, u64::from(config.protocol_fee_bps), 10_000);
+
+    let team = lamports - treasury - first_referrer - second_referrer - protocol_fee;
+
+    let mut splits = Splits { treasury, team, first_referrer, second_referrer, protocol_fee };
+
+    // Six-account instructions predate the protocol fee; the program pays it to the team
+    if recipients.is_some_and(|recipients| recipients.protocol_fee.is_none()) {
+        splits.team += splits.protocol_fee;
+        splits.protocol_fee = 0;
+    }
+
+    // Treasury overflow is routed to the team wallet
+    if treasury_balance.is_some_and(|balance| balance >= config.treasury_soft_cap) {
+        splits.team += splits.treasury;
+        splits.treasury = 0;
+    }
+
+    if let Some(recipients) = recipients.filter(|_| config.top_up_fresh_referrers) {
+        let payees = Payees {
+            treasury: &recipients.treasury,
+            team: &recipients.team,
+            first_referrer: &recipients.first_referrer,
+            second_referrer: &recipients.second_referrer,
+            protocol_fee: recipients.protocol_fee.as_ref().unwrap_or(&recipients.team),
+        };
+        let referrer_balances = [recipients.first_referrer_balance, recipients.second_referrer_balance];
+        top_up_fresh_referrers(&mut splits, &payees, referrer_balances, config.rent_exempt_minimum);
+    }
+
+    splits
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use simo_distribution_interface::{REF_MAX, TREASURY_PCT};
+
+    fn deployed_config() -> DistributorConfig {
+        DistributorConfig::from_return_data(&config_return_data(890_880)).unwrap()
+    }
+
+    #[test]
+    fn decodes_get_config_return_data() {
+        let config = deployed_config();
+
+        assert_eq!(config.treasury_pct, TREASURY_PCT);
+        assert_eq!(config.ref_max, REF_MAX);
+        assert_eq!(config.rent_exempt_minimum, 890_880);
+        assert_eq!(DistributorConfig::from_return_data(&[0; CONFIG_LEN - 1]), None);
+    }
+
+    #[test]
+    fn matches_the_program_split_for_the_deployed_config() {
+        let config = deployed_config();
+        let amounts = [0, 1, 99, 100, 10_001, 1_000_000_000, 10_000_000_000, u64::MAX / 2, u64::MAX];
+
+        for amount in amounts {
+            for (first, second) in [(false, false), (true, false), (false, true), (true, true)] {
+                assert_eq!(
+                    compute_distribution_split(amount, first, second, &config, None, None),
+                    compute_splits(amount, first, second),
+                    "amount {amount} referrers {first}/{second}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn one_sol_with_both_referrers() {
+        let splits = compute_distribution_split(1_000_000_000, true, true, &deployed_config(), None, None);
+
+        assert_eq!(
+            splits,
+            Splits {
+                treasury: 500_000_000,
+                team: 240_000_000,
+                first_referrer: 200_000_000,
+                second_referrer: 50_000_000,
+                protocol_fee: 10_000_000,
+            }
+        );
+    }
+
+    #[test]
+    fn applies_fee_fallback_soft_cap_and_top_up_from_the_config() {
+        let config = DistributorConfig { treasury_soft_cap: 1_000, top_up_fresh_referrers: true, ..deployed_config() };
+        let recipients = SplitRecipients {
+            treasury: Pubkey::new_unique(),
+            team: Pubkey::new_unique(),
+            protocol_fee: None,
+            first_referrer: Pubkey::new_unique(),
+            second_referrer: Pubkey::new_unique(),
+            first_referrer_balance: 0,
+            second_referrer_balance: 1,
+        };
+
+        let capped = compute_distribution_split(3_000_000, true, false, &config, Some(1_000), Some(&recipients));
+        // With the treasury share routed to the team there is nothing to top the referrer up from
+        assert_eq!(capped, Splits { treasury: 0, team: 3_000_000, first_referrer: 0, second_referrer: 0, protocol_fee: 0 });
+
+        let topped_up = compute_distribution_split(3_000_000, true, false, &config, Some(0), Some(&recipients));
+        assert_eq!(topped_up.first_referrer, 890_880);
+        assert_eq!(topped_up.treasury, 1_500_000 - (890_880 - 600_000));
+        assert_eq!(topped_up.protocol_fee, 0);
+    }
+}
@@ -0,0 +1,120 @@
+/**
+ * Checks the TypeScript builders against the golden byte vectors the Rust interface is tested with
+ * (interface/tests/fixtures). Run with `npm test` from client/.
+ */
+
+import { test } from 'node:test';
+import assert from 'node:assert/strict';
+import { readFileSync } from 'node:fs';
+import { PublicKey, SystemProgram, SYSVAR_INSTRUCTIONS_PUBKEY } from '@solana/web3.js';
+import {
+  SWEEP_ALL,
+  createGetConfigInstruction,
+  createPaymentDistributionInstruction,
+  decodeDistributorConfig,
+  type ExpectedSplit
+} from '../direct-web3-client.js';
+
+const fixturesDir = new URL('../../interface/tests/fixtures/', import.meta.url);
+
+/** Fixture lines without comments or blanks */
+const fixtureLines = (name: string): string[] =>
+  readFileSync(new URL(name, fixturesDir), 'utf8')
+    .split(/\r?\n/)
+    .map(line => line.trim())
+    .filter(line => line !== '' && !line.startsWith('#'));
+
+const hexBytes = (name: string): Buffer =>
+  Buffer.from(fixtureLines(name).join('').replace(/\s+/g, ''), 'hex');
+
+/** Distinct keys named after their fixture position, so a misplaced account shows up as a wrong key */
+const names = fixtureLines('accounts.txt');
+const key = (name: string): string => {
+  const index = names.indexOf(name);
+  assert.notEqual(index, -1, `${name} is not in accounts.txt`);
+  return new PublicKey(Buffer.alloc(32, index + 1)).toBase58();
+};
+
+const PROGRAM_ID = new PublicKey(Buffer.alloc(32, 0xee)).toBase58();
+
+/** The quote in distribution_quoted.hex: 1 SOL with both referrers */
+const ONE_SOL_QUOTE: ExpectedSplit = {
+  treasury: 500_000_000n,
+  team: 240_000_000n,
+  firstReferrer: 200_000_000n,
+  secondReferrer: 50_000_000n,
+  protocolFee: 10_000_000n
+};
+
+const build = (lamports: bigint, referrers: boolean, expectedSplit: ExpectedSplit | null = null, tip = 0n) =>
+  createPaymentDistributionInstruction({
+    programId: PROGRAM_ID,
+    payer: key('payer'),
+    lamports,
+    treasuryWallet: key('treasury'),
+    teamWallet: key('team'),
+    protocolFeeWallet: key('protocol_fee'),
+    firstReferrer: referrers ? key('first_referrer') : null,
+    secondReferrer: referrers ? key('second_referrer') : null,
+    expectedSplit,
+    tip,
+    tipsWallet: tip > 0n ? key('tips_account') : null
+  });
+
+test('account order', () => {
+  const ix = build(1_000_000_000n, true, null, 5_000_000n);
+  const at = (name: string): string => ix.keys[names.indexOf(name)].pubkey.toBase58();
+
+  assert.equal(ix.keys.length, names.length);
+  for (const name of ['payer', 'treasury', 'team', 'first_referrer', 'second_referrer', 'protocol_fee', 'tips_account']) {
+    assert.equal(at(name), key(name), name);
+  }
+  assert.equal(at('system_program'), SystemProgram.programId.toBase58());
+  assert.equal(at('instructions_sysvar'), SYSVAR_INSTRUCTIONS_PUBKEY.toBase58());
+  assert.ok(ix.keys[0].isSigner && ix.keys.slice(1).every(meta => !meta.isSigner));
+});
+
+test('distribution without quote', () => {
+  assert.deepEqual(build(1_000_000_000n, true).data, hexBytes('distribution.hex'));
+});
+
+test('distribution with quote', () => {
+  assert.deepEqual(build(1_000_000_000n, true, ONE_SOL_QUOTE).data, hexBytes('distribution_quoted.hex'));
+});
+
+test('distribution with quote and tip', () => {
+  assert.deepEqual(build(1_000_000_000n, true, ONE_SOL_QUOTE, 5_000_000n).data, hexBytes('distribution_tipped.hex'));
+});
+
+test('sweep', () => {
+  const ix = build(SWEEP_ALL, false);
+
+  assert.deepEqual(ix.data, hexBytes('sweep.hex'));
+  assert.equal(ix.keys[names.indexOf('first_referrer')].pubkey.toBase58(), key('payer'));
+  assert.equal(ix.keys[names.indexOf('second_referrer')].pubkey.toBase58(), key('payer'));
+});
+
+test('get config', () => {
+  const ix = createGetConfigInstruction(PROGRAM_ID);
+
+  assert.deepEqual(ix.data, hexBytes('get_config.hex'));
+  assert.equal(ix.keys.length, 0);
+});
+
+test('get config return data', () => {
+  const config = decodeDistributorConfig(hexBytes('config_return_data.hex'));
+
+  assert.deepEqual(config, {
+    treasuryPct: 50,
+    referralPct: 20,
+    referralMax: 200_000_000n,
+    referralDecayShift: 2,
+    referralTotalPctMax: 25,
+    protocolFeeBps: 100,
+    rentExemptMinimum: 890_880n,
+    treasurySoftCap: 0xffffffffffffffffn,
+    referralMinBalance: 0n,
+    requireMemo: false,
+    topUpFreshReferrers: false
+  });
+});
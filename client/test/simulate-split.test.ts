@@ -0,0 +1,87 @@
+/**
+ * Checks the split simulator against the Rust golden numbers for 1 SOL with both referrers
+ * (interface tests and the client crate's one_sol_with_both_referrers)
+ */
+
+import { test } from 'node:test';
+import assert from 'node:assert/strict';
+import { readFileSync } from 'node:fs';
+import { Connection, PublicKey, VersionedTransaction } from '@solana/web3.js';
+import { computeDistributionSplit, decodeDistributorConfig, type ExpectedSplit } from '../direct-web3-client.js';
+import { formatSplit, quoteSplit, simulateSplit } from '../simulate-split.js';
+
+/** GetConfig return data for the shipped constants, from the golden vectors */
+const configReturnData = Buffer.from(
+  readFileSync(new URL('../../interface/tests/fixtures/config_return_data.hex', import.meta.url), 'utf8')
+    .split(/\r?\n/)
+    .filter(line => !line.trim().startsWith('#'))
+    .join('')
+    .replace(/\s+/g, ''),
+  'hex'
+);
+
+const ONE_SOL = 1_000_000_000n;
+const ONE_SOL_SPLIT: ExpectedSplit = {
+  treasury: 500_000_000n,
+  team: 240_000_000n,
+  firstReferrer: 200_000_000n,
+  secondReferrer: 50_000_000n,
+  protocolFee: 10_000_000n
+};
+
+const wallet = (seed: number): string => new PublicKey(Buffer.alloc(32, seed)).toBase58();
+const PROGRAM_ID = wallet(0xee);
+
+/** An RPC node with the program deployed and every wallet funded with 1 SOL */
+const cluster = (): Connection => ({
+  getBalance: async () => Number(ONE_SOL),
+  simulateTransaction: async (tx: VersionedTransaction) => {
+    const getConfig = tx.message.compiledInstructions[0].data.length === 1;
+    return {
+      context: { slot: 1 },
+      value: {
+        err: null,
+        logs: [],
+        returnData: getConfig ? { programId: PROGRAM_ID, data: [configReturnData.toString('base64'), 'base64'] } : null
+      }
+    };
+  }
+}) as unknown as Connection;
+
+test('one SOL with both referrers from the shipped config', () => {
+  const config = decodeDistributorConfig(configReturnData);
+
+  assert.deepEqual(computeDistributionSplit(ONE_SOL, true, true, config), ONE_SOL_SPLIT);
+});
+
+test('quote from the live config', async () => {
+  const params = {
+    programId: PROGRAM_ID,
+    payer: wallet(1),
+    lamports: ONE_SOL,
+    firstReferrer: wallet(2),
+    secondReferrer: wallet(3),
+    wallets: { treasury: wallet(4), team: wallet(5), protocolFee: wallet(6) }
+  };
+
+  const { split } = await quoteSplit(cluster(), params);
+
+  assert.deepEqual(split, ONE_SOL_SPLIT);
+  assert.equal(
+    formatSplit(split),
+    'treasury         500000000\nteam             240000000\nfirst referrer   200000000\n' +
+      'second referrer  50000000\nprotocol fee     10000000'
+  );
+  assert.deepEqual(await simulateSplit(cluster(), params, split), { err: null, logs: [] });
+});
+
+test('self-referral is quoted as no referrer', async () => {
+  const { split } = await quoteSplit(cluster(), {
+    programId: PROGRAM_ID,
+    payer: wallet(1),
+    lamports: ONE_SOL,
+    firstReferrer: wallet(1)
+  });
+
+  assert.deepEqual(split, { ...ONE_SOL_SPLIT, team: 490_000_000n, firstReferrer: 0n, secondReferrer: 0n });
+});
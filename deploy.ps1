@@ -86,7 +86,7 @@ if ($Airdrop -and $Network -ne "mainnet") {
 
 # Verify program ID in contract matches keypair
 Write-Host "Step 5: Verifying program ID in contract"
-$contractRsPath = "contract/contract.rs"
+$contractRsPath = "interface/src/lib.rs"
 if (Test-Path $contractRsPath) {
     $contractRsContent = Get-Content $contractRsPath -Raw
     $programIdStart = 'declare_id!("'
@@ -97,7 +97,7 @@ if (Test-Path $contractRsPath) {
         $endPos = $contractRsContent.IndexOf($programIdEnd, $startPos)
         if ($endPos -ge 0) {
             $contractProgramId = $contractRsContent.Substring($startPos, $endPos - $startPos)
-            Write-Host "Program ID in contract.rs: $contractProgramId"
+            Write-Host "Program ID in ${contractRsPath}: $contractProgramId"
             if ($contractProgramId -ne $programId) {
                 Write-Host "ERROR: Program ID in contract ($contractProgramId) does not match keypair ($programId)" -ForegroundColor Red
                 Write-Host "Please rebuild the contract with the correct program ID." -ForegroundColor Red
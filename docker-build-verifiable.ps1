@@ -109,9 +109,9 @@ function Build-SmartContract {
     $programId = solana address -k $ProgramKeypairPath
     Write-Host "Program ID: $programId"
     
-    # Update the program ID in the contract
+    # Update the program ID in the interface crate (the program re-exports it)
     Write-Host "Updating program ID in contract..."
-    $contractRsPath = "contract/contract.rs"
+    $contractRsPath = "interface/src/lib.rs"
     
     if (Test-Path $contractRsPath) {
         $contractRsContent = Get-Content $contractRsPath -Raw
@@ -126,7 +126,7 @@ function Build-SmartContract {
             $endPos = $contractRsContent.IndexOf($oldProgramIdEnd, $startPos)
             if ($endPos -ge 0) {
                 $oldProgramId = $contractRsContent.Substring($startPos, $endPos - $startPos)
-                Write-Host "Found program ID in contract.rs: $oldProgramId"
+                Write-Host "Found program ID in ${contractRsPath}: $oldProgramId"
                 
                 $newContent = $contractRsContent.Replace(
                     "$oldProgramIdStart$oldProgramId$oldProgramIdEnd",
@@ -153,53 +153,15 @@ function Build-SmartContract {
     $solanaVersion = docker exec -t payment-distributor-builder bash -c "solana --version" 2>&1
     Write-Host "Using Solana version: $solanaVersion"
     
-    # Check for contract file
-    if (-not (Test-Path "contract/contract.rs")) {
-        Write-Host "Error: contract.rs not found in contract directory" -ForegroundColor Red
+    # Check for the workspace manifest (the repo is mounted at /app)
+    if (-not (Test-Path "program/Cargo.toml")) {
+        Write-Host "Error: program/Cargo.toml not found" -ForegroundColor Red
         exit 1
     }
     
-    # Copy contract to container
-    docker cp "contract/contract.rs" "payment-distributor-builder:/app/src/lib.rs"
-    
-    # Create a temporary Cargo.toml with pinned dependencies
-    $tempCargoFile = [System.IO.Path]::GetTempFileName()
-    @"
-[package]
-name = "payment-distributor"
-version = "0.1.0"
-description = "Payment distribution smart contract for Solana"
-edition = "2021"
-
-[lib]
-crate-type = ["cdylib", "lib"]
-name = "payment_distributor"
-path = "src/lib.rs"
-
-[dependencies]
-solana-program = "2.2.0"  # Match your stable CLI version
-solana-security-txt = "1.1.1"  # Latest stable version
-
-[profile.release]
-opt-level = "z"
-lto = "fat"
-codegen-units = 1
-panic = "abort"
-strip = true
-overflow-checks = true
-incremental = false
-debug = false
-"@ | Set-Content $tempCargoFile
-    
-    docker cp $tempCargoFile "payment-distributor-builder:/app/Cargo.toml"
-    Remove-Item $tempCargoFile
-    
-    # Create src directory (contract is already copied to /app/src/lib.rs)
-    docker exec -t payment-distributor-builder bash -c "mkdir -p /app/src"
-    
     # Build the contract
     Write-Host "Building with cargo build-sbf in release mode..."
-    docker exec -t payment-distributor-builder bash -c "cd /app && cargo build-sbf --manifest-path=Cargo.toml --sbf-out-dir=target/deploy -- --locked"
+    docker exec -t payment-distributor-builder bash -c "cd /app && cargo build-sbf --manifest-path=program/Cargo.toml --sbf-out-dir=target/deploy -- --locked"
     if ($LASTEXITCODE -ne 0) {
         Write-Host "Error building smart contract with cargo build-bpf" -ForegroundColor Red
         exit 1
@@ -0,0 +1,131 @@
+//! Instruction builders for the layouts the program decodes
+
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    system_program, sysvar,
+};
+
+use crate::{Splits, GET_CONFIG};
+
+// Distribution in the full account layout; absent referrers are filled with the payer, and the
+// Instructions sysvar is always passed so builds that require a memo accept it too
+pub fn distribution(
+    payer: &Pubkey,
+    treasury: &Pubkey,
+    team: &Pubkey,
+    referrers: [Option<&Pubkey>; 2],
+    protocol_fee: &Pubkey,
+    amount: u64,
+    quote: Option<&Splits>,
+) -> Instruction {
+    let mut data = Vec::with_capacity(58);
+    data.extend_from_slice(&amount.to_le_bytes());
+    data.extend(referrers.map(|referrer| u8::from(referrer.is_some())));
+    if let Some(quote) = quote {
+        for lamports in [quote.treasury, quote.team, quote.first_referrer, quote.second_referrer, quote.protocol_fee] {
+            data.extend_from_slice(&lamports.to_le_bytes());
+        }
+    }
+
+    let [first_referrer, second_referrer] = referrers.map(|referrer| referrer.unwrap_or(payer));
+    Instruction {
+        program_id: crate::ID,
+        accounts: vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new(*treasury, false),
+            AccountMeta::new(*team, false),
+            AccountMeta::new(*first_referrer, false),
+            AccountMeta::new(*second_referrer, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+            AccountMeta::new(*protocol_fee, false),
+            AccountMeta::new_readonly(sysvar::instructions::ID, false),
+        ],
+        data,
+    }
+}
+
+// Adds a tip to a distribution built above; the tip follows any quote, so add it last
+pub fn add_tip(instruction: &mut Instruction, tips_account: &Pubkey, tip: u64) {
+    instruction.data.extend_from_slice(&tip.to_le_bytes());
+    instruction.accounts.push(AccountMeta::new(*tips_account, false));
+}
+
+// Read-only config query; simulate it and decode the return data
+pub fn get_config() -> Instruction {
+    Instruction { program_id: crate::ID, accounts: vec![], data: vec![GET_CONFIG] }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{compute_splits, parse_instruction, ParsedDistribution, ParsedInstruction};
+
+    fn parse(instruction: &Instruction) -> ParsedDistribution {
+        let keys: Vec<Pubkey> = instruction.accounts.iter().map(|meta| meta.pubkey).collect();
+        match parse_instruction(&instruction.data, &keys) {
+            Ok(ParsedInstruction::Distribution(parsed)) => *parsed,
+            other => panic!("expected a distribution, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn distribution_round_trips_through_the_parser() {
+        let [payer, treasury, team, first, fee] = [(); 5].map(|_| Pubkey::new_unique());
+        let quote = compute_splits(1_000_000_000, true, false);
+
+        let instruction =
+            distribution(&payer, &treasury, &team, [Some(&first), None], &fee, 1_000_000_000, Some(&quote));
+
+        assert_eq!(instruction.program_id, crate::ID);
+        assert_eq!(instruction.data.len(), 50);
+        assert_eq!(instruction.accounts.len(), 8);
+        assert!(instruction.accounts[0].is_signer);
+        assert!(instruction.accounts.iter().skip(1).all(|meta| !meta.is_signer));
+        assert_eq!(instruction.accounts[4].pubkey, payer);
+        assert_eq!(instruction.accounts[5], AccountMeta::new_readonly(system_program::ID, false));
+        assert_eq!(instruction.accounts[7], AccountMeta::new_readonly(sysvar::instructions::ID, false));
+        assert_eq!(
+            parse(&instruction),
+            ParsedDistribution {
+                amount: 1_000_000_000,
+                sweep_all: false,
+                payer,
+                treasury,
+                team,
+                first_referrer: Some(first),
+                second_referrer: None,
+                protocol_fee_account: Some(fee),
+                expected_split: Some(quote),
+                tip: 0,
+                tips_account: None,
+            }
+        );
+    }
+
+    #[test]
+    fn tip_follows_the_quote_and_the_tips_account_is_last() {
+        let [payer, treasury, team, fee, tips] = [(); 5].map(|_| Pubkey::new_unique());
+
+        let mut unquoted = distribution(&payer, &treasury, &team, [None, None], &fee, 1_000, None);
+        add_tip(&mut unquoted, &tips, 7);
+        let quote = compute_splits(1_000, false, false);
+        let mut quoted = distribution(&payer, &treasury, &team, [None, None], &fee, 1_000, Some(&quote));
+        add_tip(&mut quoted, &tips, 7);
+
+        for (instruction, len) in [(unquoted, 18), (quoted, 58)] {
+            assert_eq!(instruction.data.len(), len);
+            assert_eq!(instruction.accounts[8], AccountMeta::new(tips, false));
+            let parsed = parse(&instruction);
+            assert_eq!((parsed.tip, parsed.tips_account), (7, Some(tips)));
+        }
+    }
+
+    #[test]
+    fn get_config_is_the_tag_byte_alone() {
+        let instruction = get_config();
+
+        assert!(instruction.accounts.is_empty());
+        assert_eq!(parse_instruction(&instruction.data, &[]), Ok(ParsedInstruction::GetConfig));
+    }
+}
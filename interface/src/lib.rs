@@ -0,0 +1,649 @@
+//! Instruction layouts, split math and errors for the Simo Payment Distributor
+
+use solana_program::{program_error::ProgramError, pubkey::Pubkey};
+
+pub mod instruction;
+
+// Program ID - new ID from the generated keypair
+solana_program::declare_id!("6CGfhGv77UGNVXHYAi3hZJDozf2D7c6cagRC45e7WY7z");
+
+// Constants as u8 to save space
+pub const TREASURY_PCT: u8 = 50;
+pub const PROTOCOL_FEE_BPS: u16 = 100; // Taken out of the team's share
+pub const TREASURY_SOFT_CAP: u64 = u64::MAX; // Treasury share goes to team at this balance; MAX disables
+
+// Referral schedule: level n gets REF_PCT and REF_MAX divided by 4^n (20%, 5%, 1.25%...)
+pub const REF_PCT: u8 = 20;
+pub const REF_MAX: u64 = 200_000_000;
+pub const REF_DECAY_SHIFT: u32 = 2;
+pub const REF_TOTAL_PCT_MAX: u8 = 25; // Cap on combined referral shares; a fresh-referrer top-up can exceed it
+pub const REF_MIN_BALANCE: u64 = 0; // Referrers below this balance earn nothing; 0 disables
+
+// Require an SPL Memo (order reference) in the same transaction; needs the Instructions sysvar
+pub const REQUIRE_MEMO: bool = false;
+pub const MEMO_PROGRAM_IDS: [Pubkey; 2] = [
+    solana_program::pubkey!("MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr"),
+    solana_program::pubkey!("Memo1UhkJRfHyvLMcVucJwxXeuD728EqVDDwQDxFMNo"),
+];
+
+// Top up a referrer's payout to the rent-exempt minimum when their wallet is empty, out of the
+// treasury share. Off by default: on small payments the treasury gets less than TREASURY_PCT
+// and referrers more than REF_TOTAL_PCT_MAX
+pub const TOP_UP_FRESH_REFERRERS: bool = false;
+
+// Amount sentinel: distribute the payer's whole balance, leaving the rent-exempt minimum behind
+pub const SWEEP_ALL: u64 = u64::MAX;
+
+// Parameter invariants, checked at compile time since there is no config account
+const _: () = {
+    assert!(fixed_shares_fit(TREASURY_PCT, REF_TOTAL_PCT_MAX, PROTOCOL_FEE_BPS));
+    assert!(direct_referrer_uncapped(REF_PCT, REF_TOTAL_PCT_MAX));
+    assert!(decay_shift_valid(REF_DECAY_SHIFT));
+};
+
+// Team takes the remainder, so the fixed shares must leave room for it
+const fn fixed_shares_fit(treasury_pct: u8, ref_total_pct_max: u8, protocol_fee_bps: u16) -> bool {
+    treasury_pct as u64 * 100 + ref_total_pct_max as u64 * 100 + protocol_fee_bps as u64 <= 10_000
+}
+
+// The direct referrer's share is never clipped by the combined cap
+const fn direct_referrer_uncapped(ref_pct: u8, ref_total_pct_max: u8) -> bool {
+    ref_pct <= ref_total_pct_max
+}
+
+// Each level must be smaller than the last, and the shift for level 1 must be valid
+const fn decay_shift_valid(ref_decay_shift: u32) -> bool {
+    ref_decay_shift > 0 && ref_decay_shift < 64
+}
+
+// Read-only queries are a single tag byte, too short to be a distribution
+pub const GET_CONFIG: u8 = 0;
+
+// Custom error codes returned as ProgramError::Custom
+#[repr(u32)]
+pub enum DistributorError {
+    ZeroAmount = 0,
+    InsufficientPayerBalance = 1,
+    SplitMismatch = 2,
+    AccountNotWritable = 3,
+    ExecutableAccount = 4,
+    InvalidRecipient = 5,
+    MissingMemo = 6,
+}
+
+impl From<DistributorError> for ProgramError {
+    fn from(e: DistributorError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
+
+// GetConfig return data (40 bytes, integers LE):
+// [treasury pct u8][ref pct u8][ref max u64][ref decay shift u8][ref total pct max u8]
+// [protocol fee bps u16][rent-exempt minimum u64][treasury soft cap u64][ref min balance u64]
+// [require memo u8][top up fresh referrers u8]
+// The rent-exempt minimum (0-byte account, from the Rent sysvar) is what a sweep leaves behind
+// and what a fresh referrer is topped up to
+pub const CONFIG_LEN: usize = 40;
+
+pub fn config_return_data(rent_exempt_minimum: u64) -> [u8; CONFIG_LEN] {
+    let mut data = [0u8; CONFIG_LEN];
+    data[0] = TREASURY_PCT;
+    data[1] = REF_PCT;
+    data[2..10].copy_from_slice(&REF_MAX.to_le_bytes());
+    data[10] = REF_DECAY_SHIFT as u8;
+    data[11] = REF_TOTAL_PCT_MAX;
+    data[12..14].copy_from_slice(&PROTOCOL_FEE_BPS.to_le_bytes());
+    data[14..22].copy_from_slice(&rent_exempt_minimum.to_le_bytes());
+    data[22..30].copy_from_slice(&TREASURY_SOFT_CAP.to_le_bytes());
+    data[30..38].copy_from_slice(&REF_MIN_BALANCE.to_le_bytes());
+    data[38] = u8::from(REQUIRE_MEMO);
+    data[39] = u8::from(TOP_UP_FRESH_REFERRERS);
+    data
+}
+
+// Lamports owed to each recipient for a payment
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Splits {
+    pub treasury: u64,
+    pub team: u64,
+    pub first_referrer: u64,
+    pub second_referrer: u64,
+    pub protocol_fee: u64,
+}
+
+// Optional quote after the flags: [treasury, team, first ref, second ref, protocol fee] as u64 LE.
+// Distributions are 8-10 bytes without a quote and exactly 50 with one, plus 8 for a tip (18 or 58);
+// any other length is rejected so a quote in an outdated layout can't slip through unchecked
+pub fn decode_quote(instruction_data: &[u8]) -> Result<Option<Splits>, ProgramError> {
+    let quote = match instruction_data.len() {
+        8..=10 | 18 => return Ok(None),
+        50 | 58 => &instruction_data[10..50],
+        _ => return Err(ProgramError::InvalidInstructionData),
+    };
+    let quoted = |i: usize| u64::from_le_bytes(quote[i * 8..i * 8 + 8].try_into().unwrap());
+    Ok(Some(Splits {
+        treasury: quoted(0),
+        team: quoted(1),
+        first_referrer: quoted(2),
+        second_referrer: quoted(3),
+        protocol_fee: quoted(4),
+    }))
+}
+
+// Optional tip as the trailing u64 LE, paid on top of the amount to the tips account (account 8)
+pub fn decode_tip(instruction_data: &[u8]) -> Result<u64, ProgramError> {
+    let tip = match instruction_data.len() {
+        8..=10 | 50 => return Ok(0),
+        18 => &instruction_data[10..18],
+        58 => &instruction_data[50..58],
+        _ => return Err(ProgramError::InvalidInstructionData),
+    };
+    Ok(u64::from_le_bytes(tip.try_into().unwrap()))
+}
+
+// Human-readable view of a distribution instruction, for explorers and tooling
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ParsedDistribution {
+    pub amount: u64,
+    pub sweep_all: bool, // Amount resolves to the payer's balance minus the sweep reserve
+    pub payer: Pubkey,
+    pub treasury: Pubkey,
+    pub team: Pubkey,
+    pub first_referrer: Option<Pubkey>,
+    pub second_referrer: Option<Pubkey>,
+    pub protocol_fee_account: Option<Pubkey>, // None for the six-account layout, whose fee goes to the team
+    pub expected_split: Option<Splits>,
+    pub tip: u64, // Paid on top of the amount, outside the split
+    pub tips_account: Option<Pubkey>,
+}
+
+// Any instruction the program accepts, decoded
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ParsedInstruction {
+    Distribution(Box<ParsedDistribution>),
+    GetConfig, // Read-only query, answered with config_return_data
+}
+
+// Decode raw instruction data and its account keys in program order
+pub fn parse_instruction(
+    instruction_data: &[u8],
+    account_keys: &[Pubkey],
+) -> Result<ParsedInstruction, ProgramError> {
+    if instruction_data.len() < 8 {
+        return match instruction_data {
+            [GET_CONFIG] => Ok(ParsedInstruction::GetConfig),
+            _ => Err(ProgramError::InvalidInstructionData),
+        };
+    }
+    if account_keys.len() < 6 {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+
+    let amount = u64::from_le_bytes(instruction_data[0..8].try_into().unwrap());
+    let has_first_referrer = instruction_data.get(8).is_some_and(|&flag| flag != 0);
+    let has_second_referrer = instruction_data.get(9).is_some_and(|&flag| flag != 0);
+
+    Ok(ParsedInstruction::Distribution(Box::new(ParsedDistribution {
+        amount,
+        sweep_all: amount == SWEEP_ALL,
+        payer: account_keys[0],
+        treasury: account_keys[1],
+        team: account_keys[2],
+        first_referrer: has_first_referrer.then_some(account_keys[3]),
+        second_referrer: has_second_referrer.then_some(account_keys[4]),
+        protocol_fee_account: account_keys.get(6).copied(),
+        expected_split: decode_quote(instruction_data)?,
+        tip: decode_tip(instruction_data)?,
+        tips_account: account_keys.get(8).copied(),
+    })))
+}
+
+// Split math shared by the processor and the quote check; team takes the remainder
+pub fn compute_splits(amount: u64, has_first_referrer: bool, has_second_referrer: bool) -> Splits {
+    let treasury = share_of(amount, u64::from(TREASURY_PCT), 100);
+
+    let mut referral_budget = share_of(amount, u64::from(REF_TOTAL_PCT_MAX), 100);
+
+    let first_referrer = if has_first_referrer {
+        referral_share(amount, 0).min(referral_budget)
+    } else { 0 };
+    referral_budget -= first_referrer;
+
+    let second_referrer = if has_second_referrer {
+        referral_share(amount, 1).min(referral_budget)
+    } else { 0 };
+
+    let protocol_fee = share_of(amount, u64::from(PROTOCOL_FEE_BPS), 10_000);
+
+    let team = amount - treasury - first_referrer - second_referrer - protocol_fee;
+
+    Splits { treasury, team, first_referrer, second_referrer, protocol_fee }
+}
+
+// Share for a referral level (0 = direct referrer), before the combined cap
+#[inline]
+fn referral_share(amount: u64, level: u32) -> u64 {
+    let shift = REF_DECAY_SHIFT * level;
+    (share_of(amount, u64::from(REF_PCT), 100) >> shift).min(REF_MAX >> shift)
+}
+
+// floor(amount * numerator / denominator) without overflowing for any amount.
+// Needs 0 < denominator <= 2^32 and numerator <= denominator (the split math uses 100 and 10_000)
+#[inline]
+pub fn share_of(amount: u64, numerator: u64, denominator: u64) -> u64 {
+    debug_assert!(denominator <= 1 << 32 && numerator <= denominator, "share_of: fraction out of range");
+    amount / denominator * numerator + amount % denominator * numerator / denominator
+}
+
+// Wallet behind each share; the fee share's wallet is the team wallet when there's no fee account
+#[derive(Clone, Copy, Debug)]
+pub struct Payees<'a> {
+    pub treasury: &'a Pubkey,
+    pub team: &'a Pubkey,
+    pub first_referrer: &'a Pubkey,
+    pub second_referrer: &'a Pubkey,
+    pub protocol_fee: &'a Pubkey,
+}
+
+// Fresh referrer wallets must end up rent-exempt: an empty referrer wallet whose payout is below the
+// minimum is topped up to it from the treasury share, or treated as absent (share to team) when the
+// treasury share can't cover the whole top-up, since a partial one would still fail the transfer
+pub fn top_up_fresh_referrers(
+    splits: &mut Splits,
+    payees: &Payees,
+    referrer_balances: [u64; 2],
+    rent_exempt_minimum: u64,
+) {
+    for (level, balance) in referrer_balances.into_iter().enumerate() {
+        let (wallet, share) = match level {
+            0 => (payees.first_referrer, splits.first_referrer),
+            _ => (payees.second_referrer, splits.second_referrer),
+        };
+        // Every share this wallet fills lands in one transfer, so the combined payout counts
+        let payout: u64 = [
+            (payees.treasury, splits.treasury),
+            (payees.team, splits.team),
+            (payees.first_referrer, splits.first_referrer),
+            (payees.second_referrer, splits.second_referrer),
+            (payees.protocol_fee, splits.protocol_fee),
+        ]
+        .into_iter()
+        .filter(|(key, _)| *key == wallet)
+        .map(|(_, lamports)| lamports)
+        .sum();
+        if share == 0 || payout >= rent_exempt_minimum || balance != 0 {
+            continue;
+        }
+
+        // Moving treasury lamports to the treasury wallet itself wouldn't raise its payout
+        let top_up = rent_exempt_minimum - payout;
+        let available = if payees.treasury == wallet { 0 } else { splits.treasury };
+        let (treasury_delta, new_share) = if top_up <= available {
+            (top_up, share + top_up)
+        } else {
+            splits.team += share;
+            (0, 0)
+        };
+        splits.treasury -= treasury_delta;
+        match level {
+            0 => splits.first_referrer = new_share,
+            _ => splits.second_referrer = new_share,
+        }
+    }
+}
+
+// Properties of the split math: `cargo kani` proves them for every input, `cargo test` checks boundaries
+#[cfg(any(kani, test))]
+mod verification {
+    use super::*;
+
+    fn compute_splits_is_sound(amount: u64, has_first_referrer: bool, has_second_referrer: bool) {
+        // Overflow or underflow anywhere in here fails the proof
+        let splits = compute_splits(amount, has_first_referrer, has_second_referrer);
+
+        // Conservation: every lamport is assigned exactly once
+        assert_eq!(
+            splits.treasury + splits.team + splits.first_referrer + splits.second_referrer + splits.protocol_fee,
+            amount
+        );
+
+        // Cap bounds
+        assert!(splits.first_referrer <= REF_MAX);
+        assert!(splits.second_referrer <= REF_MAX >> REF_DECAY_SHIFT);
+        assert!(
+            splits.first_referrer + splits.second_referrer
+                <= share_of(amount, u64::from(REF_TOTAL_PCT_MAX), 100)
+        );
+
+        // Absent referrers are paid nothing
+        assert!(has_first_referrer || splits.first_referrer == 0);
+        assert!(has_second_referrer || splits.second_referrer == 0);
+    }
+
+    fn share_of_matches_wide_arithmetic(amount: u64, numerator: u64, denominator: u64) {
+        let wide = u128::from(amount) * u128::from(numerator) / u128::from(denominator);
+        assert_eq!(u128::from(share_of(amount, numerator, denominator)), wide);
+    }
+
+    #[cfg(kani)]
+    #[kani::proof]
+    fn compute_splits_proof() {
+        compute_splits_is_sound(kani::any(), kani::any(), kani::any());
+    }
+
+    #[cfg(kani)]
+    #[kani::proof]
+    fn share_of_proof() {
+        let numerator: u64 = kani::any();
+        let denominator: u64 = kani::any();
+        kani::assume(denominator > 0 && denominator <= 1 << 32 && numerator <= denominator);
+        share_of_matches_wide_arithmetic(kani::any(), numerator, denominator);
+    }
+
+    #[cfg(test)]
+    const AMOUNTS: [u64; 10] = [0, 1, 99, 100, 101, 9_999, 10_000, 1_000_000_000, u64::MAX - 1, u64::MAX];
+
+    #[test]
+    fn compute_splits_is_sound_at_boundaries() {
+        for amount in AMOUNTS {
+            for (has_first_referrer, has_second_referrer) in [(false, false), (true, false), (false, true), (true, true)] {
+                compute_splits_is_sound(amount, has_first_referrer, has_second_referrer);
+            }
+        }
+    }
+
+    #[test]
+    fn share_of_matches_wide_arithmetic_at_boundaries() {
+        for denominator in [1, 100, 10_000, 1 << 32] {
+            for numerator in [0, 1, denominator / 2, denominator - 1, denominator] {
+                for amount in AMOUNTS {
+                    share_of_matches_wide_arithmetic(amount, numerator, denominator);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fixed_shares_must_leave_room_for_team() {
+        assert!(fixed_shares_fit(50, 25, 2_500));
+        assert!(!fixed_shares_fit(50, 25, 2_501));
+        assert!(!fixed_shares_fit(80, 25, 0));
+        assert!(!fixed_shares_fit(u8::MAX, u8::MAX, u16::MAX));
+    }
+
+    #[test]
+    fn direct_referrer_must_fit_combined_cap() {
+        assert!(direct_referrer_uncapped(25, 25));
+        assert!(!direct_referrer_uncapped(26, 25));
+    }
+
+    #[test]
+    fn decay_shift_must_shrink_levels_without_overflowing() {
+        assert!(!decay_shift_valid(0));
+        assert!(decay_shift_valid(1));
+        assert!(decay_shift_valid(63));
+        assert!(!decay_shift_valid(64));
+    }
+
+    #[test]
+    fn no_referrers_split_between_treasury_team_and_fee() {
+        let splits = compute_splits(1_000_000_000, false, false);
+
+        assert_eq!(
+            splits,
+            Splits {
+                treasury: 500_000_000,
+                team: 490_000_000,
+                first_referrer: 0,
+                second_referrer: 0,
+                protocol_fee: 10_000_000,
+            }
+        );
+    }
+
+    #[test]
+    fn referral_shares_below_caps_follow_the_schedule() {
+        let splits = compute_splits(100_000_000, true, true);
+
+        assert_eq!(splits.first_referrer, 20_000_000);
+        assert_eq!(splits.second_referrer, 5_000_000);
+        assert_eq!(splits.team, 100_000_000 - 50_000_000 - 20_000_000 - 5_000_000 - 1_000_000);
+    }
+
+    #[test]
+    fn referral_shares_are_capped_and_the_excess_goes_to_team() {
+        let splits = compute_splits(10_000_000_000, true, true);
+
+        assert_eq!(splits.first_referrer, REF_MAX);
+        assert_eq!(splits.second_referrer, REF_MAX >> REF_DECAY_SHIFT);
+        assert_eq!(
+            splits,
+            Splits {
+                treasury: 5_000_000_000,
+                team: 4_650_000_000,
+                first_referrer: 200_000_000,
+                second_referrer: 50_000_000,
+                protocol_fee: 100_000_000,
+            }
+        );
+    }
+
+    #[test]
+    fn absent_referrer_share_stays_with_team() {
+        let both = compute_splits(100_000_000, true, true);
+        let first_only = compute_splits(100_000_000, true, false);
+
+        assert_eq!(first_only.second_referrer, 0);
+        assert_eq!(first_only.team, both.team + both.second_referrer);
+    }
+
+    // Rent-exempt minimum for a zero-data account at current rent
+    const RENT_EXEMPT_MINIMUM: u64 = 890_880;
+
+    fn payees(keys: &[Pubkey; 5]) -> Payees<'_> {
+        Payees {
+            treasury: &keys[0],
+            team: &keys[1],
+            first_referrer: &keys[2],
+            second_referrer: &keys[3],
+            protocol_fee: &keys[4],
+        }
+    }
+
+    #[test]
+    fn fresh_referrer_is_topped_up_to_the_minimum_from_treasury() {
+        let keys = [(); 5].map(|_| Pubkey::new_unique());
+        let mut splits = compute_splits(3_000_000, true, false);
+
+        top_up_fresh_referrers(&mut splits, &payees(&keys), [0, 0], RENT_EXEMPT_MINIMUM);
+
+        assert_eq!(splits.first_referrer, RENT_EXEMPT_MINIMUM);
+        assert_eq!(splits.treasury, 1_500_000 - (RENT_EXEMPT_MINIMUM - 600_000));
+        assert_eq!(
+            splits.treasury + splits.team + splits.first_referrer + splits.second_referrer + splits.protocol_fee,
+            3_000_000
+        );
+    }
+
+    #[test]
+    fn fresh_referrer_is_treated_as_absent_when_treasury_cannot_cover_the_top_up() {
+        let keys = [(); 5].map(|_| Pubkey::new_unique());
+        let computed = compute_splits(1_000_000, true, true);
+        let mut splits = computed;
+
+        top_up_fresh_referrers(&mut splits, &payees(&keys), [0, 0], RENT_EXEMPT_MINIMUM);
+
+        // 500,000 of treasury can't lift a 200,000 payout to the minimum; no partial top-up
+        assert_eq!(splits.first_referrer, 0);
+        assert_eq!(splits.second_referrer, 0);
+        assert_eq!(splits.treasury, computed.treasury);
+        assert_eq!(splits.team, computed.team + computed.first_referrer + computed.second_referrer);
+    }
+
+    #[test]
+    fn top_up_stops_when_the_treasury_runs_out() {
+        let keys = [(); 5].map(|_| Pubkey::new_unique());
+        let computed = compute_splits(2_000_000, true, true);
+        let mut splits = computed;
+
+        top_up_fresh_referrers(&mut splits, &payees(&keys), [0, 0], RENT_EXEMPT_MINIMUM);
+
+        // The first top-up fits; what's left of the treasury share can't lift the second referrer
+        assert_eq!(splits.first_referrer, RENT_EXEMPT_MINIMUM);
+        assert_eq!(splits.treasury, computed.treasury - (RENT_EXEMPT_MINIMUM - computed.first_referrer));
+        assert_eq!(splits.second_referrer, 0);
+        assert_eq!(splits.team, computed.team + computed.second_referrer);
+    }
+
+    #[test]
+    fn wallet_in_both_referrer_slots_is_topped_up_once_for_the_combined_payout() {
+        let mut keys = [(); 5].map(|_| Pubkey::new_unique());
+        keys[3] = keys[2];
+        let computed = compute_splits(3_000_000, true, true);
+        let mut splits = computed;
+
+        top_up_fresh_referrers(&mut splits, &payees(&keys), [0, 0], RENT_EXEMPT_MINIMUM);
+
+        assert_eq!(splits.first_referrer + splits.second_referrer, RENT_EXEMPT_MINIMUM);
+        assert_eq!(splits.second_referrer, computed.second_referrer);
+        assert_eq!(
+            splits.treasury,
+            computed.treasury - (RENT_EXEMPT_MINIMUM - computed.first_referrer - computed.second_referrer)
+        );
+    }
+
+    #[test]
+    fn funded_referrer_wallet_is_not_topped_up() {
+        let keys = [(); 5].map(|_| Pubkey::new_unique());
+        let computed = compute_splits(1_000_000, true, true);
+        let mut splits = computed;
+
+        top_up_fresh_referrers(&mut splits, &payees(&keys), [1, 1], RENT_EXEMPT_MINIMUM);
+
+        assert_eq!(splits, computed);
+    }
+
+    #[test]
+    fn quote_decodes_only_from_fifty_bytes_with_or_without_a_tip() {
+        let mut data = [0u8; 58];
+        data[0..8].copy_from_slice(&1_000u64.to_le_bytes());
+        for (i, lamports) in [500u64, 240, 200, 50, 10].into_iter().enumerate() {
+            data[10 + i * 8..18 + i * 8].copy_from_slice(&lamports.to_le_bytes());
+        }
+
+        let quote = Splits { treasury: 500, team: 240, first_referrer: 200, second_referrer: 50, protocol_fee: 10 };
+        assert_eq!(decode_quote(&data[..50]), Ok(Some(quote)));
+        assert_eq!(decode_quote(&data), Ok(Some(quote)));
+        for len in [8, 9, 10, 18] {
+            assert_eq!(decode_quote(&data[..len]), Ok(None));
+        }
+    }
+
+    #[test]
+    fn tip_is_the_trailing_word_of_a_tipped_layout() {
+        let mut data = [0u8; 58];
+        data[10..18].copy_from_slice(&7u64.to_le_bytes());
+        data[50..58].copy_from_slice(&9u64.to_le_bytes());
+
+        assert_eq!(decode_tip(&data[..18]), Ok(7));
+        assert_eq!(decode_tip(&data), Ok(9));
+        for len in [8, 9, 10, 50] {
+            assert_eq!(decode_tip(&data[..len]), Ok(0));
+        }
+    }
+
+    #[test]
+    fn quote_or_tip_of_any_other_length_is_rejected() {
+        let data = [0u8; 59];
+        // 42 bytes is the quote layout from before the protocol fee share
+        for len in [11, 17, 19, 42, 49, 51, 57, 59] {
+            assert_eq!(decode_quote(&data[..len]), Err(ProgramError::InvalidInstructionData));
+            assert_eq!(decode_tip(&data[..len]), Err(ProgramError::InvalidInstructionData));
+        }
+    }
+
+    #[test]
+    fn parses_get_config() {
+        assert_eq!(parse_instruction(&[GET_CONFIG], &[]), Ok(ParsedInstruction::GetConfig));
+        assert_eq!(parse_instruction(&[1], &[]), Err(ProgramError::InvalidInstructionData));
+        assert_eq!(parse_instruction(&[], &[]), Err(ProgramError::InvalidInstructionData));
+    }
+
+    #[test]
+    fn parses_distribution_in_account_order() {
+        let keys: [Pubkey; 8] = std::array::from_fn(|_| Pubkey::new_unique());
+        let mut data = [0u8; 10];
+        data[0..8].copy_from_slice(&1_000_000u64.to_le_bytes());
+        data[8] = 1;
+
+        assert_eq!(
+            parse_instruction(&data, &keys),
+            Ok(ParsedInstruction::Distribution(Box::new(ParsedDistribution {
+                amount: 1_000_000,
+                sweep_all: false,
+                payer: keys[0],
+                treasury: keys[1],
+                team: keys[2],
+                first_referrer: Some(keys[3]),
+                second_referrer: None,
+                protocol_fee_account: Some(keys[6]),
+                expected_split: None,
+                tip: 0,
+                tips_account: None,
+            })))
+        );
+    }
+
+    #[test]
+    fn parses_tip_and_tips_account() {
+        let keys: [Pubkey; 9] = std::array::from_fn(|_| Pubkey::new_unique());
+        let mut data = [0u8; 18];
+        data[0..8].copy_from_slice(&1_000_000u64.to_le_bytes());
+        data[10..18].copy_from_slice(&5_000u64.to_le_bytes());
+
+        let Ok(ParsedInstruction::Distribution(parsed)) = parse_instruction(&data, &keys) else {
+            panic!("expected a distribution");
+        };
+        assert_eq!(parsed.tip, 5_000);
+        assert_eq!(parsed.tips_account, Some(keys[8]));
+        assert_eq!(parsed.expected_split, None);
+    }
+
+    #[test]
+    fn parses_six_account_distribution_without_fee_account() {
+        let keys: [Pubkey; 6] = std::array::from_fn(|_| Pubkey::new_unique());
+
+        let Ok(ParsedInstruction::Distribution(parsed)) = parse_instruction(&SWEEP_ALL.to_le_bytes(), &keys) else {
+            panic!("expected a distribution");
+        };
+        assert!(parsed.sweep_all);
+        assert_eq!(parsed.protocol_fee_account, None);
+        assert_eq!(parse_instruction(&SWEEP_ALL.to_le_bytes(), &keys[..5]), Err(ProgramError::NotEnoughAccountKeys));
+    }
+
+    #[test]
+    fn config_return_data_layout() {
+        let data = config_return_data(890_880);
+
+        assert_eq!(data.len(), CONFIG_LEN);
+        assert_eq!(data[0], TREASURY_PCT);
+        assert_eq!(data[1], REF_PCT);
+        assert_eq!(data[2..10], REF_MAX.to_le_bytes());
+        assert_eq!(u32::from(data[10]), REF_DECAY_SHIFT);
+        assert_eq!(data[11], REF_TOTAL_PCT_MAX);
+        assert_eq!(data[12..14], PROTOCOL_FEE_BPS.to_le_bytes());
+        assert_eq!(data[14..22], 890_880u64.to_le_bytes());
+        assert_eq!(data[22..30], TREASURY_SOFT_CAP.to_le_bytes());
+        assert_eq!(data[30..38], REF_MIN_BALANCE.to_le_bytes());
+        assert_eq!(data[38], u8::from(REQUIRE_MEMO));
+        assert_eq!(data[39], u8::from(TOP_UP_FRESH_REFERRERS));
+    }
+}
@@ -0,0 +1,142 @@
+//! Golden byte vectors for the instruction layouts (fixtures are shared with client builders)
+
+use simo_distribution_interface::{
+    compute_splits, config_return_data, decode_quote, decode_tip,
+    instruction::{add_tip, distribution, get_config},
+    parse_instruction, ParsedDistribution, ParsedInstruction, SWEEP_ALL,
+};
+use solana_program::{instruction::Instruction, pubkey::Pubkey, system_program, sysvar};
+
+// Fixture lines without comments or blanks
+fn fixture_lines(fixture: &str) -> impl Iterator<Item = &str> {
+    fixture.lines().map(str::trim).filter(|line| !line.is_empty() && !line.starts_with('#'))
+}
+
+fn hex_bytes(fixture: &str) -> Vec<u8> {
+    fixture_lines(fixture)
+        .flat_map(str::split_whitespace)
+        .map(|byte| u8::from_str_radix(byte, 16).unwrap())
+        .collect()
+}
+
+// Account keys named after their fixture position, so a misread index shows up as a wrong key
+fn fixture_accounts() -> (Vec<Pubkey>, impl Fn(&str) -> usize) {
+    let names: Vec<String> =
+        fixture_lines(include_str!("fixtures/accounts.txt")).map(String::from).collect();
+    let keys = names.iter().map(|_| Pubkey::new_unique()).collect();
+    (keys, move |name: &str| names.iter().position(|n| n == name).unwrap())
+}
+
+// Builds a distribution from the fixture keys, optionally paying both referrers and quoting the split
+fn build(
+    keys: &[Pubkey],
+    index: impl Fn(&str) -> usize,
+    amount: u64,
+    referrers: bool,
+    quote: bool,
+) -> Instruction {
+    let key = |name: &str| &keys[index(name)];
+    let referrers = [key("first_referrer"), key("second_referrer")].map(|key| Some(key).filter(|_| referrers));
+    let quote = quote.then(|| compute_splits(amount, true, true));
+    distribution(key("payer"), key("treasury"), key("team"), referrers, key("protocol_fee"), amount, quote.as_ref())
+}
+
+fn parse_distribution(instruction_data: &[u8], account_keys: &[Pubkey]) -> ParsedDistribution {
+    match parse_instruction(instruction_data, account_keys) {
+        Ok(ParsedInstruction::Distribution(parsed)) => *parsed,
+        other => panic!("expected a distribution, got {other:?}"),
+    }
+}
+
+#[test]
+fn account_order() {
+    let (keys, index) = fixture_accounts();
+    let mut instruction = build(&keys, &index, 1_000_000_000, true, false);
+    add_tip(&mut instruction, &keys[index("tips_account")], 5_000_000);
+
+    // The builder puts each account where the fixture names it
+    let built = |name: &str| instruction.accounts[index(name)].pubkey;
+    assert_eq!(keys.len(), 9);
+    assert_eq!(instruction.accounts.len(), keys.len());
+    for name in ["payer", "treasury", "team", "first_referrer", "second_referrer", "protocol_fee", "tips_account"] {
+        assert_eq!(built(name), keys[index(name)], "{name}");
+    }
+    assert_eq!(built("system_program"), system_program::ID);
+    assert_eq!(built("instructions_sysvar"), sysvar::instructions::ID);
+
+    // The parser reads them back from the same positions
+    let data = hex_bytes(include_str!("fixtures/distribution_tipped.hex"));
+    let parsed = parse_distribution(&data, &keys);
+    assert_eq!(parsed.payer, keys[index("payer")]);
+    assert_eq!(parsed.treasury, keys[index("treasury")]);
+    assert_eq!(parsed.team, keys[index("team")]);
+    assert_eq!(parsed.first_referrer, Some(keys[index("first_referrer")]));
+    assert_eq!(parsed.second_referrer, Some(keys[index("second_referrer")]));
+    assert_eq!(parsed.protocol_fee_account, Some(keys[index("protocol_fee")]));
+    assert_eq!(parsed.tips_account, Some(keys[index("tips_account")]));
+}
+
+#[test]
+fn distribution_without_quote() {
+    let (keys, index) = fixture_accounts();
+    let data = hex_bytes(include_str!("fixtures/distribution.hex"));
+    let instruction = build(&keys, index, 1_000_000_000, true, false);
+
+    assert_eq!(instruction.data, data);
+    let parsed = parse_distribution(&data, &keys);
+    assert_eq!(parsed.amount, 1_000_000_000);
+    assert!(!parsed.sweep_all);
+    assert_eq!(parsed.expected_split, None);
+}
+
+#[test]
+fn distribution_with_quote() {
+    let (keys, index) = fixture_accounts();
+    let data = hex_bytes(include_str!("fixtures/distribution_quoted.hex"));
+    let instruction = build(&keys, index, 1_000_000_000, true, true);
+    let quote = compute_splits(1_000_000_000, true, true);
+
+    assert_eq!(instruction.data, data);
+    assert_eq!(decode_quote(&data), Ok(Some(quote)));
+    assert_eq!(parse_distribution(&data, &keys).expected_split, Some(quote));
+}
+
+#[test]
+fn distribution_with_quote_and_tip() {
+    let (keys, index) = fixture_accounts();
+    let data = hex_bytes(include_str!("fixtures/distribution_tipped.hex"));
+    let mut instruction = build(&keys, &index, 1_000_000_000, true, true);
+    add_tip(&mut instruction, &keys[index("tips_account")], 5_000_000);
+
+    assert_eq!(instruction.data, data);
+    assert_eq!(decode_quote(&data), Ok(Some(compute_splits(1_000_000_000, true, true))));
+    assert_eq!(decode_tip(&data), Ok(5_000_000));
+}
+
+#[test]
+fn sweep() {
+    let (keys, index) = fixture_accounts();
+    let data = hex_bytes(include_str!("fixtures/sweep.hex"));
+    let instruction = build(&keys, index, SWEEP_ALL, false, false);
+
+    assert_eq!(instruction.data, data);
+    let parsed = parse_distribution(&data, &keys);
+    assert!(parsed.sweep_all);
+    assert_eq!(parsed.first_referrer, None);
+    assert_eq!(parsed.second_referrer, None);
+}
+
+#[test]
+fn get_config_query() {
+    let data = hex_bytes(include_str!("fixtures/get_config.hex"));
+
+    assert_eq!(get_config().data, data);
+    assert_eq!(parse_instruction(&data, &[]), Ok(ParsedInstruction::GetConfig));
+}
+
+#[test]
+fn get_config_return_data() {
+    let data = hex_bytes(include_str!("fixtures/config_return_data.hex"));
+
+    assert_eq!(data, config_return_data(890_880));
+}
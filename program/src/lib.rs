@@ -0,0 +1,401 @@
+//! Minimal Payment Distributor Smart Contract
+
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    program::{invoke, set_return_data},
+    pubkey::Pubkey,
+    system_instruction,
+    sysvar::{instructions::load_instruction_at_checked, rent::Rent, Sysvar},
+    program_error::ProgramError,
+};
+
+use solana_security_txt::security_txt;
+
+use simo_distribution_interface::{
+    compute_splits, config_return_data, decode_quote, decode_tip, top_up_fresh_referrers,
+    DistributorError, Payees, Splits, GET_CONFIG, MEMO_PROGRAM_IDS, REF_MIN_BALANCE, REQUIRE_MEMO,
+    SWEEP_ALL, TOP_UP_FRESH_REFERRERS, TREASURY_SOFT_CAP,
+};
+#[cfg(feature = "audit-assertions")]
+use simo_distribution_interface::{share_of, REF_DECAY_SHIFT, REF_MAX, REF_TOTAL_PCT_MAX};
+
+// Program ID lives in the interface crate so clients and the program share it
+pub use simo_distribution_interface::{check_id, id, ID};
+
+// Logging levels: log_info! is stripped by `log-off`, log_debug! only exists with `log-debug`
+macro_rules! log_info {
+    ($($arg:tt)*) => {
+        #[cfg(not(feature = "log-off"))]
+        solana_program::msg!($($arg)*);
+    };
+}
+macro_rules! log_debug {
+    ($($arg:tt)*) => {
+        #[cfg(all(feature = "log-debug", not(feature = "log-off")))]
+        solana_program::msg!($($arg)*);
+    };
+}
+
+// Transfer order (each payee exactly once); shares for the same wallet are paid at its first slot
+#[derive(Clone, Copy)]
+enum Payee {
+    Treasury,
+    Team,
+    ProtocolFee,
+    FirstReferrer,
+    SecondReferrer,
+}
+const PAYOUT_ORDER: [Payee; 5] = [
+    Payee::Treasury,
+    Payee::Team,
+    Payee::ProtocolFee,
+    Payee::FirstReferrer,
+    Payee::SecondReferrer,
+];
+
+// Use the entrypoint! macro instead of manual entrypoint
+solana_program::entrypoint!(process_instruction);
+
+security_txt! {
+    name: "Project Simo Distribution",
+    project_url: "https://projectsimo.io",
+    contacts: "discord:https://discord.gg/projectsimo",
+    policy: "https://projectsimo.io/security-policy",
+    preferred_languages: "en",
+    source_code: "https://github.com/darkbrewery/SimoDistribution"
+}
+
+// Add inline attribute to encourage compiler to inline this function
+#[inline]
+fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    // Refuse to run under a program id clients don't expect (clones, proxies)
+    if *program_id != crate::ID {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    // Parse instruction data
+    if instruction_data.len() < 8 {
+        return match instruction_data {
+            [GET_CONFIG] => get_config(),
+            _ => Err(ProgramError::InvalidInstructionData),
+        };
+    }
+
+    let requested_amount = u64::from_le_bytes(instruction_data[0..8].try_into().unwrap());
+    let has_first_referrer = instruction_data.get(8).is_some_and(|&flag| flag != 0);
+    let has_second_referrer = instruction_data.get(9).is_some_and(|&flag| flag != 0);
+    let quote = decode_quote(instruction_data)?;
+    let tip = decode_tip(instruction_data)?;
+
+    // Extract accounts
+    let iter = &mut accounts.iter();
+    let payer = next_account_info(iter)?;
+    let treasury = next_account_info(iter)?;
+    let team = next_account_info(iter)?;
+
+    // Always extract both referrer accounts, regardless of flags
+    let first_referrer = next_account_info(iter)?;
+    let second_referrer = next_account_info(iter)?;
+    let system_program = next_account_info(iter)?;
+
+    // Callers built for the six-account layout predate the protocol fee; their fee stays with the team
+    let protocol_fee_account = next_account_info(iter).ok();
+
+    // Tips go to account 8, after the Instructions sysvar; only needed when there is a tip
+    let tips_account = if tip > 0 {
+        Some(accounts.get(8).ok_or(ProgramError::NotEnoughAccountKeys)?)
+    } else {
+        None
+    };
+
+    // Verify system program ID
+    if *system_program.key != solana_program::system_program::ID {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    // Validate every account lamports move through before the first transfer
+    if !payer.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    check_money_account(payer)?;
+    check_money_account(treasury)?;
+    check_money_account(team)?;
+    if let Some(protocol_fee_account) = protocol_fee_account {
+        check_money_account(protocol_fee_account)?;
+    }
+    if let Some(tips_account) = tips_account {
+        check_money_account(tips_account)?;
+    }
+    if has_first_referrer {
+        check_money_account(first_referrer)?;
+    }
+    if has_second_referrer {
+        check_money_account(second_referrer)?;
+    }
+
+    // Every settlement must carry an order reference when memos are required
+    if REQUIRE_MEMO {
+        let instructions_sysvar = next_account_info(iter)?;
+        if !has_memo(instructions_sysvar)? {
+            log_info!("No SPL Memo with an order reference in the transaction");
+            return Err(DistributorError::MissingMemo.into());
+        }
+    }
+
+    // Ineligible referrers are treated as absent, so their share stays with the team
+    let has_first_referrer = has_first_referrer && referrer_eligible(first_referrer, payer);
+    let has_second_referrer = has_second_referrer && referrer_eligible(second_referrer, payer);
+    log_debug!("Referrers paid: first {} second {}", has_first_referrer, has_second_referrer);
+
+    // Resolve sweep mode against the payer's current balance, keeping the payer rent-exempt and the tip
+    let rent_exempt_minimum = Rent::get()?.minimum_balance(0);
+    let amount = if requested_amount == SWEEP_ALL {
+        payer.lamports().saturating_sub(rent_exempt_minimum).saturating_sub(tip)
+    } else {
+        requested_amount
+    };
+    log_debug!("Distributing {} lamports", amount);
+
+    // Nothing to distribute
+    if amount == 0 {
+        return Err(DistributorError::ZeroAmount.into());
+    }
+
+    // Fail up front rather than part way through the transfers; a payer left with dust below the
+    // rent-exempt minimum would fail the last transfer anyway
+    let remaining = payer
+        .lamports()
+        .checked_sub(amount)
+        .and_then(|remaining| remaining.checked_sub(tip))
+        .ok_or(DistributorError::InsufficientPayerBalance)?;
+    if remaining != 0 && remaining < rent_exempt_minimum {
+        return Err(DistributorError::InsufficientPayerBalance.into());
+    }
+
+    // Calculate amounts
+    let mut splits = compute_splits(amount, has_first_referrer, has_second_referrer);
+
+    // No fee account (six-account layout): the fee is paid to the team as before the fee existed
+    if protocol_fee_account.is_none() {
+        splits.team += splits.protocol_fee;
+        splits.protocol_fee = 0;
+    }
+
+    // Referral shares before any top-up, for the audit below
+    #[cfg(feature = "audit-assertions")]
+    let computed = splits;
+
+    // Keep the hot treasury wallet bounded by routing overflow to the team wallet
+    #[allow(clippy::absurd_extreme_comparisons)] // Always false while the cap is disabled (u64::MAX)
+    let over_soft_cap = treasury.lamports() >= TREASURY_SOFT_CAP;
+    if over_soft_cap {
+        splits.team += splits.treasury;
+        splits.treasury = 0;
+    }
+
+    // Recipient and share for each payee; one wallet can fill several slots
+    let slot = |payee: Payee, splits: &Splits| match payee {
+        Payee::Treasury => (treasury, splits.treasury),
+        Payee::Team => (team, splits.team),
+        Payee::ProtocolFee => (protocol_fee_account.unwrap_or(team), splits.protocol_fee),
+        Payee::FirstReferrer => (first_referrer, splits.first_referrer),
+        Payee::SecondReferrer => (second_referrer, splits.second_referrer),
+    };
+
+    // Fresh referrer wallets must end up rent-exempt, so top tiny first payouts up from treasury
+    if TOP_UP_FRESH_REFERRERS {
+        let payees = Payees {
+            treasury: treasury.key,
+            team: team.key,
+            first_referrer: first_referrer.key,
+            second_referrer: second_referrer.key,
+            protocol_fee: slot(Payee::ProtocolFee, &splits).0.key,
+        };
+        let referrer_balances = [first_referrer.lamports(), second_referrer.lamports()];
+        top_up_fresh_referrers(&mut splits, &payees, referrer_balances, rent_exempt_minimum);
+    }
+
+    // Devnet/review builds: the split about to be paid conserves the amount, and the referral caps
+    // hold unless a fresh-referrer top-up raised a share
+    #[cfg(feature = "audit-assertions")]
+    {
+        log_debug!("audit: amount {} split {:?}", amount, splits);
+        assert_eq!(
+            splits.treasury + splits.team + splits.first_referrer + splits.second_referrer + splits.protocol_fee,
+            amount,
+            "audit: split does not conserve amount"
+        );
+        let topped_up = splits.first_referrer > computed.first_referrer
+            || splits.second_referrer > computed.second_referrer;
+        if !topped_up {
+            assert!(splits.first_referrer <= REF_MAX, "audit: first referrer over cap");
+            assert!(splits.second_referrer <= REF_MAX >> REF_DECAY_SHIFT, "audit: second referrer over cap");
+            assert!(
+                splits.first_referrer + splits.second_referrer
+                    <= share_of(amount, u64::from(REF_TOTAL_PCT_MAX), 100),
+                "audit: referral outflow over cap"
+            );
+        }
+    }
+
+    // Optional client quote must match exactly
+    if quote.is_some_and(|quote| quote != splits) {
+        log_info!("Quote mismatch, program split: {:?}", splits);
+        return Err(DistributorError::SplitMismatch.into());
+    }
+
+    #[cfg(feature = "audit-assertions")]
+    let payer_before = payer.lamports();
+
+    // Payouts in configured order; shares for the same wallet collapse into one transfer
+    let mut payouts = PAYOUT_ORDER.map(|payee| slot(payee, &splits));
+    coalesce_payouts(&mut payouts, |account| account.key);
+
+    // Transfers, skipping empty shares (including referrers that weren't flagged)
+    for (recipient, lamports) in payouts {
+        if lamports > 0 {
+            invoke(
+                &system_instruction::transfer(payer.key, recipient.key, lamports),
+                &[payer.clone(), recipient.clone(), system_program.clone()],
+            )?;
+        }
+    }
+
+    // The tip sits outside the split and goes to the tips account whole
+    if let Some(tips_account) = tips_account {
+        log_info!("Tip {} lamports to {}", tip, tips_account.key);
+        invoke(
+            &system_instruction::transfer(payer.key, tips_account.key, tip),
+            &[payer.clone(), tips_account.clone(), system_program.clone()],
+        )?;
+    }
+
+    // Devnet/review builds: the payer lost exactly the amount and tip (unless it was also paid)
+    #[cfg(feature = "audit-assertions")]
+    {
+        let mut paid_back = [treasury, team].into_iter().chain(protocol_fee_account).chain(tips_account);
+        if !paid_back.any(|account| account.key == payer.key) {
+            assert_eq!(payer_before - payer.lamports(), amount + tip, "audit: payer debit does not match amount");
+        }
+    }
+
+    Ok(())
+}
+
+// Moves every share onto the first payout to the same wallet, leaving zeros behind
+#[inline]
+fn coalesce_payouts<T>(payouts: &mut [(T, u64)], key: impl Fn(&T) -> &Pubkey) {
+    for i in 1..payouts.len() {
+        if let Some(j) = (0..i).find(|&j| key(&payouts[j].0) == key(&payouts[i].0)) {
+            payouts[j].1 += payouts[i].1;
+            payouts[i].1 = 0;
+        }
+    }
+}
+
+// Accounts that send or receive lamports must be writable wallets, not programs
+#[inline]
+fn check_money_account(account: &AccountInfo) -> ProgramResult {
+    // Obviously wrong destinations first, since the runtime also makes sysvars read-only
+    if *account.key == solana_program::system_program::ID
+        || *account.key == crate::ID
+        || solana_program::sysvar::check_id(account.owner)
+    {
+        return Err(DistributorError::InvalidRecipient.into());
+    }
+    if !account.is_writable {
+        return Err(DistributorError::AccountNotWritable.into());
+    }
+    if account.executable {
+        return Err(DistributorError::ExecutableAccount.into());
+    }
+    Ok(())
+}
+
+// Anti-Sybil criteria: no self-referral and a minimum balance
+#[inline]
+#[allow(clippy::absurd_extreme_comparisons)] // Always true while the minimum is disabled (0)
+fn referrer_eligible(referrer: &AccountInfo, payer: &AccountInfo) -> bool {
+    referrer.key != payer.key && referrer.lamports() >= REF_MIN_BALANCE
+}
+
+// Scans the transaction for a non-empty SPL Memo instruction
+fn has_memo(instructions_sysvar: &AccountInfo) -> Result<bool, ProgramError> {
+    if !solana_program::sysvar::instructions::check_id(instructions_sysvar.key) {
+        return Err(ProgramError::UnsupportedSysvar);
+    }
+
+    // Loading past the last instruction fails, which ends the scan
+    let mut index = 0;
+    while let Ok(instruction) = load_instruction_at_checked(index, instructions_sysvar) {
+        if MEMO_PROGRAM_IDS.contains(&instruction.program_id) && !instruction.data.is_empty() {
+            return Ok(true);
+        }
+        index += 1;
+    }
+    Ok(false)
+}
+
+// Answers with the split parameters as return data (layout in the interface crate)
+fn get_config() -> ProgramResult {
+    set_return_data(&config_return_data(Rent::get()?.minimum_balance(0)));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_referrer_in_both_slots_is_paid_once() {
+        let (treasury, team, protocol_fee, referrer) =
+            (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let mut payouts = [
+            (treasury, 500_000),
+            (team, 240_000),
+            (protocol_fee, 10_000),
+            (referrer, 200_000),
+            (referrer, 50_000),
+        ];
+
+        coalesce_payouts(&mut payouts, |key| key);
+
+        assert_eq!(
+            payouts,
+            [
+                (treasury, 500_000),
+                (team, 240_000),
+                (protocol_fee, 10_000),
+                (referrer, 250_000),
+                (referrer, 0),
+            ]
+        );
+    }
+
+    #[test]
+    fn shared_wallet_is_paid_at_its_first_slot() {
+        let (treasury, team, referrer) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        // Six-account callers: the fee slot falls back to the team wallet with nothing in it
+        let mut payouts = [(treasury, 500_000), (team, 250_000), (team, 0), (treasury, 200_000), (referrer, 50_000)];
+
+        coalesce_payouts(&mut payouts, |key| key);
+
+        assert_eq!(payouts, [(treasury, 700_000), (team, 250_000), (team, 0), (treasury, 0), (referrer, 50_000)]);
+    }
+
+    #[test]
+    fn distinct_wallets_are_left_alone() {
+        let keys: [Pubkey; 5] = std::array::from_fn(|_| Pubkey::new_unique());
+        let mut payouts = keys.map(|key| (key, 1_000));
+        let expected = payouts;
+
+        coalesce_payouts(&mut payouts, |key| key);
+
+        assert_eq!(payouts, expected);
+    }
+}